@@ -0,0 +1,73 @@
+//! Lean runtime support for the wasm build: a bump allocator, and panic and allocation error
+//! handlers that abort, in place of the ones `casper-contract` links in by default.
+//!
+//! Every entry point call runs in a fresh wasm instance, so memory is never reused: allocating
+//! moves a pointer forward, growing the memory when it runs out, and freeing does nothing.
+use core::{
+    alloc::{GlobalAlloc, Layout},
+    arch::wasm32,
+    cell::UnsafeCell,
+    panic::PanicInfo,
+    ptr,
+};
+
+/// Size of a wasm memory page, in bytes.
+const PAGE_SIZE: usize = 64 * 1024;
+
+#[global_allocator]
+static ALLOCATOR: BumpAllocator = BumpAllocator {
+    next: UnsafeCell::new(0),
+    end: UnsafeCell::new(0),
+};
+
+/// Hands out memory from the pages grown past the initial memory of the module.
+struct BumpAllocator {
+    /// Address of the first free byte.
+    next: UnsafeCell<usize>,
+    /// Address past the last grown page, `0` until the first allocation.
+    end: UnsafeCell<usize>,
+}
+
+// The contract runs single threaded.
+unsafe impl Sync for BumpAllocator {}
+
+unsafe impl GlobalAlloc for BumpAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let next = &mut *self.next.get();
+        let end = &mut *self.end.get();
+        if *end == 0 {
+            *end = wasm32::memory_size(0) * PAGE_SIZE;
+            *next = *end;
+        }
+
+        let start = (*next + layout.align() - 1) & !(layout.align() - 1);
+        let new_next = match start.checked_add(layout.size()) {
+            Some(new_next) => new_next,
+            None => return ptr::null_mut(),
+        };
+        if new_next > *end {
+            let pages = (new_next - *end + PAGE_SIZE - 1) / PAGE_SIZE;
+            if wasm32::memory_grow(0, pages) == usize::MAX {
+                return ptr::null_mut();
+            }
+            *end += pages * PAGE_SIZE;
+        }
+        *next = new_next;
+        start as *mut u8
+    }
+
+    unsafe fn dealloc(&self, _ptr: *mut u8, _layout: Layout) {}
+}
+
+#[panic_handler]
+fn panic(_info: &PanicInfo) -> ! {
+    wasm32::unreachable()
+}
+
+#[alloc_error_handler]
+fn oom(_layout: Layout) -> ! {
+    wasm32::unreachable()
+}
+
+#[lang = "eh_personality"]
+extern "C" fn eh_personality() {}
@@ -1,5 +1,10 @@
 //! Contains definition of the ERC20 contract entry points.
-use types::{CLType, EntryPoint, EntryPointAccess, EntryPointType, EntryPoints, Parameter};
+use alloc::{collections::BTreeMap, string::String, vec, vec::Vec};
+
+use types::{
+    bytesrepr::Bytes, CLType, CLTyped, EntryPoint, EntryPointAccess, EntryPointType, EntryPoints,
+    Key, Parameter, U256,
+};
 
 pub fn endpoint(name: &str, param: Vec<Parameter>, ret: CLType) -> EntryPoint {
     EntryPoint::new(
@@ -64,6 +69,24 @@ pub fn allowance() -> EntryPoint {
     )
 }
 
+/// Returns the `balances_of` entry point.
+pub fn balances_of() -> EntryPoint {
+    endpoint(
+        "balances_of",
+        vec![Parameter::new("addresses", Vec::<Key>::cl_type())],
+        Vec::<U256>::cl_type(),
+    )
+}
+
+/// Returns the `allowances_of` entry point.
+pub fn allowances_of() -> EntryPoint {
+    endpoint(
+        "allowances_of",
+        vec![Parameter::new("pairs", Vec::<(Key, Key)>::cl_type())],
+        Vec::<U256>::cl_type(),
+    )
+}
+
 /// Returns the `approve` entry point.
 pub fn approve() -> EntryPoint {
     endpoint(
@@ -76,6 +99,338 @@ pub fn approve() -> EntryPoint {
     )
 }
 
+/// Returns the `transfer_with_memo` entry point.
+pub fn transfer_with_memo() -> EntryPoint {
+    endpoint(
+        "transfer_with_memo",
+        vec![
+            Parameter::new("recipient", CLType::Key),
+            Parameter::new("amount", CLType::U256),
+            Parameter::new("memo", CLType::String),
+        ],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `transfer_from_with_memo` entry point.
+pub fn transfer_from_with_memo() -> EntryPoint {
+    endpoint(
+        "transfer_from_with_memo",
+        vec![
+            Parameter::new("owner", CLType::Key),
+            Parameter::new("recipient", CLType::Key),
+            Parameter::new("amount", CLType::U256),
+            Parameter::new("memo", CLType::String),
+        ],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `transfer_and_call` entry point.
+pub fn transfer_and_call() -> EntryPoint {
+    endpoint(
+        "transfer_and_call",
+        vec![
+            Parameter::new("recipient_contract", CLType::Key),
+            Parameter::new("amount", CLType::U256),
+            Parameter::new("data", Bytes::cl_type()),
+        ],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `flash_loan` entry point.
+pub fn flash_loan() -> EntryPoint {
+    endpoint(
+        "flash_loan",
+        vec![
+            Parameter::new("receiver", CLType::Key),
+            Parameter::new("amount", CLType::U256),
+            Parameter::new("data", Bytes::cl_type()),
+        ],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `max_flash_loan` entry point.
+pub fn max_flash_loan() -> EntryPoint {
+    endpoint("max_flash_loan", vec![], CLType::U256)
+}
+
+/// Returns the `flash_fee` entry point.
+pub fn flash_fee() -> EntryPoint {
+    endpoint(
+        "flash_fee",
+        vec![Parameter::new("amount", CLType::U256)],
+        CLType::U256,
+    )
+}
+
+/// Returns the `set_flash_fee` entry point.
+pub fn set_flash_fee() -> EntryPoint {
+    endpoint(
+        "set_flash_fee",
+        vec![Parameter::new("basis_points", CLType::U64)],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `set_flash_fee_recipient` entry point.
+pub fn set_flash_fee_recipient() -> EntryPoint {
+    endpoint(
+        "set_flash_fee_recipient",
+        vec![Parameter::new("recipient", Option::<Key>::cl_type())],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `approve_and_call` entry point.
+pub fn approve_and_call() -> EntryPoint {
+    endpoint(
+        "approve_and_call",
+        vec![
+            Parameter::new("spender_contract", CLType::Key),
+            Parameter::new("amount", CLType::U256),
+            Parameter::new("data", Bytes::cl_type()),
+        ],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `batch_transfer_from` entry point.
+pub fn batch_transfer_from() -> EntryPoint {
+    endpoint(
+        "batch_transfer_from",
+        vec![Parameter::new("items", Vec::<(Key, Key, U256)>::cl_type())],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `batch_transfer` entry point.
+pub fn batch_transfer() -> EntryPoint {
+    endpoint(
+        "batch_transfer",
+        vec![Parameter::new("recipients", Vec::<(Key, U256)>::cl_type())],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `approve_with_expiry` entry point.
+pub fn approve_with_expiry() -> EntryPoint {
+    endpoint(
+        "approve_with_expiry",
+        vec![
+            Parameter::new("spender", CLType::Key),
+            Parameter::new("amount", CLType::U256),
+            Parameter::new("expires_at", CLType::U64),
+        ],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `permit` entry point.
+pub fn permit() -> EntryPoint {
+    endpoint(
+        "permit",
+        vec![
+            Parameter::new("owner_public_key", CLType::PublicKey),
+            Parameter::new("spender", CLType::Key),
+            Parameter::new("amount", CLType::U256),
+            Parameter::new("deadline", CLType::U64),
+            Parameter::new("signature", Bytes::cl_type()),
+        ],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `nonces` entry point.
+pub fn nonces() -> EntryPoint {
+    endpoint(
+        "nonces",
+        vec![Parameter::new("owner", CLType::Key)],
+        CLType::U64,
+    )
+}
+
+/// Returns the `domain_separator` entry point.
+pub fn domain_separator() -> EntryPoint {
+    endpoint("domain_separator", vec![], <[u8; 32]>::cl_type())
+}
+
+/// Returns the `delegate` entry point.
+pub fn delegate() -> EntryPoint {
+    endpoint(
+        "delegate",
+        vec![Parameter::new("delegatee", CLType::Key)],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `delegate_by_sig` entry point.
+pub fn delegate_by_sig() -> EntryPoint {
+    endpoint(
+        "delegate_by_sig",
+        vec![
+            Parameter::new("delegator_public_key", CLType::PublicKey),
+            Parameter::new("delegatee", CLType::Key),
+            Parameter::new("expiry", CLType::U64),
+            Parameter::new("signature", Bytes::cl_type()),
+        ],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `delegates` entry point.
+pub fn delegates() -> EntryPoint {
+    endpoint(
+        "delegates",
+        vec![Parameter::new("account", CLType::Key)],
+        Option::<Key>::cl_type(),
+    )
+}
+
+/// Returns the `get_votes` entry point.
+pub fn get_votes() -> EntryPoint {
+    endpoint(
+        "get_votes",
+        vec![Parameter::new("account", CLType::Key)],
+        CLType::U256,
+    )
+}
+
+/// Returns the `get_past_votes` entry point.
+pub fn get_past_votes() -> EntryPoint {
+    endpoint(
+        "get_past_votes",
+        vec![
+            Parameter::new("account", CLType::Key),
+            Parameter::new("timestamp", CLType::U64),
+        ],
+        CLType::U256,
+    )
+}
+
+/// Returns the `snapshot` entry point.
+pub fn snapshot() -> EntryPoint {
+    endpoint("snapshot", vec![], CLType::U64)
+}
+
+/// Returns the `balance_of_at` entry point.
+pub fn balance_of_at() -> EntryPoint {
+    endpoint(
+        "balance_of_at",
+        vec![
+            Parameter::new("address", CLType::Key),
+            Parameter::new("id", CLType::U64),
+        ],
+        CLType::U256,
+    )
+}
+
+/// Returns the `total_supply_at` entry point.
+pub fn total_supply_at() -> EntryPoint {
+    endpoint(
+        "total_supply_at",
+        vec![Parameter::new("id", CLType::U64)],
+        CLType::U256,
+    )
+}
+
+/// Returns the `transfer_with_authorization` entry point.
+pub fn transfer_with_authorization() -> EntryPoint {
+    endpoint(
+        "transfer_with_authorization",
+        vec![
+            Parameter::new("from_public_key", CLType::PublicKey),
+            Parameter::new("to", CLType::Key),
+            Parameter::new("amount", CLType::U256),
+            Parameter::new("valid_after", CLType::U64),
+            Parameter::new("valid_before", CLType::U64),
+            Parameter::new("nonce", <[u8; 32]>::cl_type()),
+            Parameter::new("signature", Bytes::cl_type()),
+            Parameter::new("fee", CLType::U256),
+        ],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `cancel_authorization` entry point.
+pub fn cancel_authorization() -> EntryPoint {
+    endpoint(
+        "cancel_authorization",
+        vec![
+            Parameter::new("from_public_key", CLType::PublicKey),
+            Parameter::new("nonce", <[u8; 32]>::cl_type()),
+            Parameter::new("signature", Bytes::cl_type()),
+        ],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `authorization_state` entry point.
+pub fn authorization_state() -> EntryPoint {
+    endpoint(
+        "authorization_state",
+        vec![
+            Parameter::new("authorizer", CLType::Key),
+            Parameter::new("nonce", <[u8; 32]>::cl_type()),
+        ],
+        CLType::Bool,
+    )
+}
+
+/// Returns the `approve_recurring` entry point.
+pub fn approve_recurring() -> EntryPoint {
+    endpoint(
+        "approve_recurring",
+        vec![
+            Parameter::new("spender", CLType::Key),
+            Parameter::new("amount_per_period", CLType::U256),
+            Parameter::new("period_ms", CLType::U64),
+        ],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `recurring_allowance` entry point.
+pub fn recurring_allowance() -> EntryPoint {
+    endpoint(
+        "recurring_allowance",
+        vec![
+            Parameter::new("owner", CLType::Key),
+            Parameter::new("spender", CLType::Key),
+        ],
+        CLType::U256,
+    )
+}
+
+/// Returns the `collect` entry point.
+pub fn collect() -> EntryPoint {
+    endpoint(
+        "collect",
+        vec![
+            Parameter::new("owner", CLType::Key),
+            Parameter::new("recipient", CLType::Key),
+            Parameter::new("amount", CLType::U256),
+        ],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `spenders_of` entry point.
+pub fn spenders_of() -> EntryPoint {
+    endpoint(
+        "spenders_of",
+        vec![Parameter::new("owner", CLType::Key)],
+        Vec::<Key>::cl_type(),
+    )
+}
+
+/// Returns the `revoke_all_allowances` entry point.
+pub fn revoke_all_allowances() -> EntryPoint {
+    endpoint("revoke_all_allowances", vec![], CLType::Unit)
+}
+
 /// Returns the `transfer_from` entry point.
 pub fn transfer_from() -> EntryPoint {
     endpoint(
@@ -89,6 +444,222 @@ pub fn transfer_from() -> EntryPoint {
     )
 }
 
+/// Returns the `authorize_operator` entry point.
+pub fn authorize_operator() -> EntryPoint {
+    endpoint(
+        "authorize_operator",
+        vec![Parameter::new("operator", CLType::Key)],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `revoke_operator` entry point.
+pub fn revoke_operator() -> EntryPoint {
+    endpoint(
+        "revoke_operator",
+        vec![Parameter::new("operator", CLType::Key)],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `is_operator_for` entry point.
+pub fn is_operator_for() -> EntryPoint {
+    endpoint(
+        "is_operator_for",
+        vec![
+            Parameter::new("operator", CLType::Key),
+            Parameter::new("holder", CLType::Key),
+        ],
+        CLType::Bool,
+    )
+}
+
+/// Returns the `operator_send` entry point.
+pub fn operator_send() -> EntryPoint {
+    endpoint(
+        "operator_send",
+        vec![
+            Parameter::new("holder", CLType::Key),
+            Parameter::new("recipient", CLType::Key),
+            Parameter::new("amount", CLType::U256),
+        ],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `register_receiver` entry point.
+pub fn register_receiver() -> EntryPoint {
+    endpoint("register_receiver", vec![], CLType::Unit)
+}
+
+/// Returns the `unregister_receiver` entry point.
+pub fn unregister_receiver() -> EntryPoint {
+    endpoint("unregister_receiver", vec![], CLType::Unit)
+}
+
+/// Returns the `set_safe_transfers` entry point.
+pub fn set_safe_transfers() -> EntryPoint {
+    endpoint(
+        "set_safe_transfers",
+        vec![Parameter::new("enabled", CLType::Bool)],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `set_allowlist_required` entry point.
+pub fn set_allowlist_required() -> EntryPoint {
+    endpoint(
+        "set_allowlist_required",
+        vec![Parameter::new("enabled", CLType::Bool)],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `grant_kyc_role` entry point.
+pub fn grant_kyc_role() -> EntryPoint {
+    endpoint(
+        "grant_kyc_role",
+        vec![Parameter::new("account", CLType::Key)],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `revoke_kyc_role` entry point.
+pub fn revoke_kyc_role() -> EntryPoint {
+    endpoint(
+        "revoke_kyc_role",
+        vec![Parameter::new("account", CLType::Key)],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `allowlist_add` entry point.
+pub fn allowlist_add() -> EntryPoint {
+    endpoint(
+        "allowlist_add",
+        vec![Parameter::new(
+            "accounts",
+            Vec::<(Key, Option<u64>)>::cl_type(),
+        )],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `allowlist_remove` entry point.
+pub fn allowlist_remove() -> EntryPoint {
+    endpoint(
+        "allowlist_remove",
+        vec![Parameter::new("accounts", Vec::<Key>::cl_type())],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `is_allowlisted` entry point.
+pub fn is_allowlisted() -> EntryPoint {
+    endpoint(
+        "is_allowlisted",
+        vec![Parameter::new("account", CLType::Key)],
+        CLType::Bool,
+    )
+}
+
+/// Returns the `blacklist` entry point.
+pub fn blacklist() -> EntryPoint {
+    endpoint(
+        "blacklist",
+        vec![Parameter::new("account", CLType::Key)],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `unblacklist` entry point.
+pub fn unblacklist() -> EntryPoint {
+    endpoint(
+        "unblacklist",
+        vec![Parameter::new("account", CLType::Key)],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `is_blacklisted` entry point.
+pub fn is_blacklisted() -> EntryPoint {
+    endpoint(
+        "is_blacklisted",
+        vec![Parameter::new("account", CLType::Key)],
+        CLType::Bool,
+    )
+}
+
+/// Returns the `detect_transfer_restriction` entry point.
+pub fn detect_transfer_restriction() -> EntryPoint {
+    endpoint(
+        "detect_transfer_restriction",
+        vec![
+            Parameter::new("from", CLType::Key),
+            Parameter::new("to", CLType::Key),
+            Parameter::new("amount", CLType::U256),
+        ],
+        CLType::U8,
+    )
+}
+
+/// Returns the `message_for_transfer_restriction` entry point.
+pub fn message_for_transfer_restriction() -> EntryPoint {
+    endpoint(
+        "message_for_transfer_restriction",
+        vec![Parameter::new("restriction_code", CLType::U8)],
+        CLType::String,
+    )
+}
+
+/// Returns the `token_metadata` entry point.
+pub fn token_metadata() -> EntryPoint {
+    endpoint(
+        "token_metadata",
+        vec![],
+        BTreeMap::<String, String>::cl_type(),
+    )
+}
+
+/// Returns the `token_info` entry point.
+pub fn token_info() -> EntryPoint {
+    endpoint(
+        "token_info",
+        vec![],
+        <((String, String, u8), U256, BTreeMap<String, String>)>::cl_type(),
+    )
+}
+
+/// Returns the `set_metadata` entry point.
+pub fn set_metadata() -> EntryPoint {
+    endpoint(
+        "set_metadata",
+        vec![
+            Parameter::new("key", CLType::String),
+            Parameter::new("value", CLType::String),
+        ],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `set_name` entry point.
+pub fn set_name() -> EntryPoint {
+    endpoint(
+        "set_name",
+        vec![Parameter::new("name", CLType::String)],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `set_symbol` entry point.
+pub fn set_symbol() -> EntryPoint {
+    endpoint(
+        "set_symbol",
+        vec![Parameter::new("symbol", CLType::String)],
+        CLType::Unit,
+    )
+}
+
 /// Returns the default set of ERC20 entry points.
 pub fn default() -> EntryPoints {
     let mut entry_points = EntryPoints::new();
@@ -99,7 +670,63 @@ pub fn default() -> EntryPoints {
     entry_points.add_entry_point(transfer());
     entry_points.add_entry_point(balance_of());
     entry_points.add_entry_point(allowance());
+    entry_points.add_entry_point(balances_of());
+    entry_points.add_entry_point(allowances_of());
     entry_points.add_entry_point(approve());
+    entry_points.add_entry_point(approve_with_expiry());
+    entry_points.add_entry_point(approve_recurring());
+    entry_points.add_entry_point(recurring_allowance());
+    entry_points.add_entry_point(collect());
+    entry_points.add_entry_point(permit());
+    entry_points.add_entry_point(nonces());
+    entry_points.add_entry_point(domain_separator());
+    entry_points.add_entry_point(delegate());
+    entry_points.add_entry_point(delegate_by_sig());
+    entry_points.add_entry_point(delegates());
+    entry_points.add_entry_point(get_votes());
+    entry_points.add_entry_point(get_past_votes());
+    entry_points.add_entry_point(snapshot());
+    entry_points.add_entry_point(balance_of_at());
+    entry_points.add_entry_point(total_supply_at());
+    entry_points.add_entry_point(transfer_with_authorization());
+    entry_points.add_entry_point(cancel_authorization());
+    entry_points.add_entry_point(authorization_state());
+    entry_points.add_entry_point(spenders_of());
+    entry_points.add_entry_point(revoke_all_allowances());
     entry_points.add_entry_point(transfer_from());
+    entry_points.add_entry_point(batch_transfer());
+    entry_points.add_entry_point(batch_transfer_from());
+    entry_points.add_entry_point(transfer_with_memo());
+    entry_points.add_entry_point(transfer_from_with_memo());
+    entry_points.add_entry_point(transfer_and_call());
+    entry_points.add_entry_point(approve_and_call());
+    entry_points.add_entry_point(flash_loan());
+    entry_points.add_entry_point(max_flash_loan());
+    entry_points.add_entry_point(flash_fee());
+    entry_points.add_entry_point(set_flash_fee());
+    entry_points.add_entry_point(set_flash_fee_recipient());
+    entry_points.add_entry_point(authorize_operator());
+    entry_points.add_entry_point(revoke_operator());
+    entry_points.add_entry_point(is_operator_for());
+    entry_points.add_entry_point(operator_send());
+    entry_points.add_entry_point(register_receiver());
+    entry_points.add_entry_point(unregister_receiver());
+    entry_points.add_entry_point(set_safe_transfers());
+    entry_points.add_entry_point(set_allowlist_required());
+    entry_points.add_entry_point(grant_kyc_role());
+    entry_points.add_entry_point(revoke_kyc_role());
+    entry_points.add_entry_point(allowlist_add());
+    entry_points.add_entry_point(allowlist_remove());
+    entry_points.add_entry_point(is_allowlisted());
+    entry_points.add_entry_point(blacklist());
+    entry_points.add_entry_point(unblacklist());
+    entry_points.add_entry_point(is_blacklisted());
+    entry_points.add_entry_point(detect_transfer_restriction());
+    entry_points.add_entry_point(message_for_transfer_restriction());
+    entry_points.add_entry_point(token_metadata());
+    entry_points.add_entry_point(token_info());
+    entry_points.add_entry_point(set_metadata());
+    entry_points.add_entry_point(set_name());
+    entry_points.add_entry_point(set_symbol());
     entry_points
 }
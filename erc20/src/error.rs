@@ -9,7 +9,7 @@ use types::ApiError;
 /// return those via the [`Error::User`] variant or equivalently via the [`ApiError::User`]
 /// variant.
 ///
-/// Such a user error should be in the range `[0..(u16::MAX - 27)]` (i.e. [0, 65508]) to avoid
+/// Such a user error should be in the range `[0..(u16::MAX - 34)]` (i.e. [0, 65501]) to avoid
 /// conflicting with the other `Error` variants.
 pub enum Error {
     /// ERC20 contract called from within an invalid context.
@@ -30,6 +30,56 @@ pub enum Error {
     BurnAmountExceedsBalance,
     /// Called a pair's function with the wrong emergency_mode.
     InadequateEmergencyMode,
+    /// Caller is not allowed to call the entry point.
+    PermissionDenied,
+    /// Metadata key is not one of the supported keys.
+    UnknownMetadataKey,
+    /// Token name is empty or too long.
+    InvalidTokenNameLength,
+    /// Token name contains a character outside of the allowed set.
+    InvalidTokenNameCharacter,
+    /// Token name collides with a named key of the installing account.
+    TokenNameAlreadyInUse,
+    /// Token symbol is empty or too long.
+    InvalidTokenSymbolLength,
+    /// Token symbol contains a character outside of the allowed set.
+    InvalidTokenSymbolCharacter,
+    /// Token decimals exceed the supported maximum.
+    InvalidTokenDecimals,
+    /// Transfer memo exceeds the maximum length.
+    MemoTooLong,
+    /// Target of a callback is not a contract package hash.
+    NotAContract,
+    /// Target contract rejected the tokens or the allowance in its callback.
+    CallbackRejected,
+    /// Recipient contract has not registered itself as a token receiver.
+    UnregisteredReceiver,
+    /// Entry point holding the reentrancy lock was entered again.
+    Reentrancy,
+    /// Caller is not an authorized operator of the holder.
+    NotAnOperator,
+    /// Period of a recurring allowance is zero.
+    InvalidPeriod,
+    /// Deadline of a signed message has passed.
+    SignatureExpired,
+    /// Signature does not match the signer and the signed message.
+    InvalidSignature,
+    /// Signed transfer authorization is submitted before its `valid_after` time.
+    AuthorizationNotYetValid,
+    /// Nonce of a signed transfer authorization was already used or canceled.
+    AuthorizationUsed,
+    /// Snapshot with the given id has not been taken.
+    InvalidSnapshotId,
+    /// Past votes are looked up for a block time that is not in the past.
+    FutureLookup,
+    /// Flash loan exceeds the amount that can be minted.
+    FlashLoanTooLarge,
+    /// Sender, recipient, owner or spender is frozen.
+    Blacklisted,
+    /// Allowlist mode is on and the sender or the recipient is not allowlisted.
+    NotAllowlisted,
+    /// Flash loan fee is above 100%.
+    InvalidFlashFee,
     /// User error.
     User(u16),
 }
@@ -44,6 +94,31 @@ const ERROR_CANNOT_MINT_TO_ZERO_HASH: u16 = u16::MAX - 5; // 65530
 const ERROR_CANNOT_BURN_FROM_ZERO_HASH: u16 = u16::MAX - 6; // 65529
 const ERROR_BURN_AMOUNT_EXCEEDS_BALANCE: u16 = u16::MAX - 7; // 65528
 const ERROR_INADEQUATE_EMERGENCY_MODE: u16 = u16::MAX - 8; // 65527
+const ERROR_PERMISSION_DENIED: u16 = u16::MAX - 9; // 65526
+const ERROR_UNKNOWN_METADATA_KEY: u16 = u16::MAX - 10; // 65525
+const ERROR_INVALID_TOKEN_NAME_LENGTH: u16 = u16::MAX - 11; // 65524
+const ERROR_INVALID_TOKEN_NAME_CHARACTER: u16 = u16::MAX - 12; // 65523
+const ERROR_TOKEN_NAME_ALREADY_IN_USE: u16 = u16::MAX - 13; // 65522
+const ERROR_INVALID_TOKEN_SYMBOL_LENGTH: u16 = u16::MAX - 14; // 65521
+const ERROR_INVALID_TOKEN_SYMBOL_CHARACTER: u16 = u16::MAX - 15; // 65520
+const ERROR_INVALID_TOKEN_DECIMALS: u16 = u16::MAX - 16; // 65519
+const ERROR_MEMO_TOO_LONG: u16 = u16::MAX - 17; // 65518
+const ERROR_NOT_A_CONTRACT: u16 = u16::MAX - 18; // 65517
+const ERROR_CALLBACK_REJECTED: u16 = u16::MAX - 19; // 65516
+const ERROR_UNREGISTERED_RECEIVER: u16 = u16::MAX - 20; // 65515
+const ERROR_REENTRANCY: u16 = u16::MAX - 21; // 65514
+const ERROR_NOT_AN_OPERATOR: u16 = u16::MAX - 22; // 65513
+const ERROR_INVALID_PERIOD: u16 = u16::MAX - 23; // 65512
+const ERROR_SIGNATURE_EXPIRED: u16 = u16::MAX - 24; // 65511
+const ERROR_INVALID_SIGNATURE: u16 = u16::MAX - 25; // 65510
+const ERROR_AUTHORIZATION_NOT_YET_VALID: u16 = u16::MAX - 26; // 65509
+const ERROR_AUTHORIZATION_USED: u16 = u16::MAX - 27; // 65508
+const ERROR_INVALID_SNAPSHOT_ID: u16 = u16::MAX - 28; // 65507
+const ERROR_FUTURE_LOOKUP: u16 = u16::MAX - 29; // 65506
+const ERROR_FLASH_LOAN_TOO_LARGE: u16 = u16::MAX - 30; // 65505
+const ERROR_BLACKLISTED: u16 = u16::MAX - 31; // 65504
+const ERROR_NOT_ALLOWLISTED: u16 = u16::MAX - 32; // 65503
+const ERROR_INVALID_FLASH_FEE: u16 = u16::MAX - 33; // 65502
 
 impl From<Error> for ApiError {
     fn from(error: Error) -> Self {
@@ -57,6 +132,31 @@ impl From<Error> for ApiError {
             Error::CannotBurnFromZeroHash => ERROR_CANNOT_BURN_FROM_ZERO_HASH,
             Error::BurnAmountExceedsBalance => ERROR_BURN_AMOUNT_EXCEEDS_BALANCE,
             Error::InadequateEmergencyMode => ERROR_INADEQUATE_EMERGENCY_MODE,
+            Error::PermissionDenied => ERROR_PERMISSION_DENIED,
+            Error::UnknownMetadataKey => ERROR_UNKNOWN_METADATA_KEY,
+            Error::InvalidTokenNameLength => ERROR_INVALID_TOKEN_NAME_LENGTH,
+            Error::InvalidTokenNameCharacter => ERROR_INVALID_TOKEN_NAME_CHARACTER,
+            Error::TokenNameAlreadyInUse => ERROR_TOKEN_NAME_ALREADY_IN_USE,
+            Error::InvalidTokenSymbolLength => ERROR_INVALID_TOKEN_SYMBOL_LENGTH,
+            Error::InvalidTokenSymbolCharacter => ERROR_INVALID_TOKEN_SYMBOL_CHARACTER,
+            Error::InvalidTokenDecimals => ERROR_INVALID_TOKEN_DECIMALS,
+            Error::MemoTooLong => ERROR_MEMO_TOO_LONG,
+            Error::NotAContract => ERROR_NOT_A_CONTRACT,
+            Error::CallbackRejected => ERROR_CALLBACK_REJECTED,
+            Error::UnregisteredReceiver => ERROR_UNREGISTERED_RECEIVER,
+            Error::Reentrancy => ERROR_REENTRANCY,
+            Error::NotAnOperator => ERROR_NOT_AN_OPERATOR,
+            Error::InvalidPeriod => ERROR_INVALID_PERIOD,
+            Error::SignatureExpired => ERROR_SIGNATURE_EXPIRED,
+            Error::InvalidSignature => ERROR_INVALID_SIGNATURE,
+            Error::AuthorizationNotYetValid => ERROR_AUTHORIZATION_NOT_YET_VALID,
+            Error::AuthorizationUsed => ERROR_AUTHORIZATION_USED,
+            Error::InvalidSnapshotId => ERROR_INVALID_SNAPSHOT_ID,
+            Error::FutureLookup => ERROR_FUTURE_LOOKUP,
+            Error::FlashLoanTooLarge => ERROR_FLASH_LOAN_TOO_LARGE,
+            Error::Blacklisted => ERROR_BLACKLISTED,
+            Error::NotAllowlisted => ERROR_NOT_ALLOWLISTED,
+            Error::InvalidFlashFee => ERROR_INVALID_FLASH_FEE,
             Error::User(user_error) => user_error,
         };
         ApiError::User(user_error)
@@ -2,7 +2,11 @@
 #![allow(non_snake_case)]
 #![allow(dead_code)]
 
-use std::collections::BTreeMap;
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec::Vec,
+};
 
 use contract::contract_api::storage;
 use types::{ContractPackageHash, Key, URef, U256};
@@ -20,6 +24,64 @@ pub enum ERC20Event {
         to: Key,
         value: U256,
     },
+    RecurringApproval {
+        owner: Key,
+        spender: Key,
+        value: U256,
+        period: u64,
+    },
+    TransferWithMemo {
+        from: Key,
+        to: Key,
+        value: U256,
+        memo: String,
+    },
+    AuthorizedOperator {
+        operator: Key,
+        holder: Key,
+    },
+    RevokedOperator {
+        operator: Key,
+        holder: Key,
+    },
+    MetadataUpdated {
+        key: String,
+        value: String,
+    },
+    AuthorizationUsed {
+        authorizer: Key,
+        nonce: [u8; 32],
+    },
+    AuthorizationCanceled {
+        authorizer: Key,
+        nonce: [u8; 32],
+    },
+    Snapshot {
+        id: u64,
+    },
+    DelegateChanged {
+        delegator: Key,
+        from_delegate: Option<Key>,
+        to_delegate: Key,
+    },
+    DelegateVotesChanged {
+        delegate: Key,
+        previous_balance: U256,
+        new_balance: U256,
+    },
+    Blacklisted {
+        account: Key,
+    },
+    Unblacklisted {
+        account: Key,
+    },
+    AllowlistAdded {
+        account: Key,
+        expires_at: Option<u64>,
+    },
+    AllowlistRemoved {
+        account: Key,
+    },
 }
 
 impl ERC20Event {
@@ -35,6 +97,53 @@ impl ERC20Event {
                 to: _,
                 value: _,
             } => "transfer",
+            ERC20Event::RecurringApproval {
+                owner: _,
+                spender: _,
+                value: _,
+                period: _,
+            } => "recurring_approval",
+            ERC20Event::TransferWithMemo {
+                from: _,
+                to: _,
+                value: _,
+                memo: _,
+            } => "transfer_with_memo",
+            ERC20Event::AuthorizedOperator {
+                operator: _,
+                holder: _,
+            } => "authorized_operator",
+            ERC20Event::RevokedOperator {
+                operator: _,
+                holder: _,
+            } => "revoked_operator",
+            ERC20Event::MetadataUpdated { key: _, value: _ } => "metadata_updated",
+            ERC20Event::AuthorizationUsed {
+                authorizer: _,
+                nonce: _,
+            } => "authorization_used",
+            ERC20Event::AuthorizationCanceled {
+                authorizer: _,
+                nonce: _,
+            } => "authorization_canceled",
+            ERC20Event::Snapshot { id: _ } => "snapshot",
+            ERC20Event::DelegateChanged {
+                delegator: _,
+                from_delegate: _,
+                to_delegate: _,
+            } => "delegate_changed",
+            ERC20Event::DelegateVotesChanged {
+                delegate: _,
+                previous_balance: _,
+                new_balance: _,
+            } => "delegate_votes_changed",
+            ERC20Event::Blacklisted { account: _ } => "blacklisted",
+            ERC20Event::Unblacklisted { account: _ } => "unblacklisted",
+            ERC20Event::AllowlistAdded {
+                account: _,
+                expires_at: _,
+            } => "allowlist_added",
+            ERC20Event::AllowlistRemoved { account: _ } => "allowlist_removed",
         }
         .to_string()
     }
@@ -44,9 +153,9 @@ pub fn contract_package_hash() -> ContractPackageHash {
     get_key::<ContractPackageHash>("contract_package_hash")
 }
 
-pub(crate) fn emit(pair_event: &ERC20Event) {
+/// Records `pair_event` as a new `URef`, tagged with the already resolved `package` hash.
+pub(crate) fn emit(package: ContractPackageHash, pair_event: &ERC20Event) {
     let mut events = Vec::new();
-    let package = contract_package_hash();
     match pair_event {
         ERC20Event::Approval {
             owner,
@@ -70,6 +179,122 @@ pub(crate) fn emit(pair_event: &ERC20Event) {
             event.insert("value", value.to_string());
             events.push(event);
         }
+        ERC20Event::RecurringApproval {
+            owner,
+            spender,
+            value,
+            period,
+        } => {
+            let mut event = BTreeMap::new();
+            event.insert("contract_package_hash", package.to_string());
+            event.insert("event_type", pair_event.type_name());
+            event.insert("owner", owner.to_string());
+            event.insert("spender", spender.to_string());
+            event.insert("value", value.to_string());
+            event.insert("period", period.to_string());
+            events.push(event);
+        }
+        ERC20Event::TransferWithMemo {
+            from,
+            to,
+            value,
+            memo,
+        } => {
+            let mut event = BTreeMap::new();
+            event.insert("contract_package_hash", package.to_string());
+            event.insert("event_type", pair_event.type_name());
+            event.insert("from", from.to_string());
+            event.insert("to", to.to_string());
+            event.insert("value", value.to_string());
+            event.insert("memo", memo.clone());
+            events.push(event);
+        }
+        ERC20Event::AuthorizedOperator { operator, holder }
+        | ERC20Event::RevokedOperator { operator, holder } => {
+            let mut event = BTreeMap::new();
+            event.insert("contract_package_hash", package.to_string());
+            event.insert("event_type", pair_event.type_name());
+            event.insert("operator", operator.to_string());
+            event.insert("holder", holder.to_string());
+            events.push(event);
+        }
+        ERC20Event::MetadataUpdated { key, value } => {
+            let mut event = BTreeMap::new();
+            event.insert("contract_package_hash", package.to_string());
+            event.insert("event_type", pair_event.type_name());
+            event.insert("key", key.clone());
+            event.insert("value", value.clone());
+            events.push(event);
+        }
+        ERC20Event::AuthorizationUsed { authorizer, nonce }
+        | ERC20Event::AuthorizationCanceled { authorizer, nonce } => {
+            let mut event = BTreeMap::new();
+            event.insert("contract_package_hash", package.to_string());
+            event.insert("event_type", pair_event.type_name());
+            event.insert("authorizer", authorizer.to_string());
+            event.insert("nonce", hex::encode(nonce));
+            events.push(event);
+        }
+        ERC20Event::Snapshot { id } => {
+            let mut event = BTreeMap::new();
+            event.insert("contract_package_hash", package.to_string());
+            event.insert("event_type", pair_event.type_name());
+            event.insert("id", id.to_string());
+            events.push(event);
+        }
+        ERC20Event::DelegateChanged {
+            delegator,
+            from_delegate,
+            to_delegate,
+        } => {
+            let mut event = BTreeMap::new();
+            event.insert("contract_package_hash", package.to_string());
+            event.insert("event_type", pair_event.type_name());
+            event.insert("delegator", delegator.to_string());
+            event.insert(
+                "from_delegate",
+                from_delegate
+                    .map(|from_delegate| from_delegate.to_string())
+                    .unwrap_or_default(),
+            );
+            event.insert("to_delegate", to_delegate.to_string());
+            events.push(event);
+        }
+        ERC20Event::DelegateVotesChanged {
+            delegate,
+            previous_balance,
+            new_balance,
+        } => {
+            let mut event = BTreeMap::new();
+            event.insert("contract_package_hash", package.to_string());
+            event.insert("event_type", pair_event.type_name());
+            event.insert("delegate", delegate.to_string());
+            event.insert("previous_balance", previous_balance.to_string());
+            event.insert("new_balance", new_balance.to_string());
+            events.push(event);
+        }
+        ERC20Event::AllowlistAdded {
+            account,
+            expires_at,
+        } => {
+            let mut event = BTreeMap::new();
+            event.insert("contract_package_hash", package.to_string());
+            event.insert("event_type", pair_event.type_name());
+            event.insert("account", account.to_string());
+            if let Some(expires_at) = expires_at {
+                event.insert("expires_at", expires_at.to_string());
+            }
+            events.push(event);
+        }
+        ERC20Event::Blacklisted { account }
+        | ERC20Event::Unblacklisted { account }
+        | ERC20Event::AllowlistRemoved { account } => {
+            let mut event = BTreeMap::new();
+            event.insert("contract_package_hash", package.to_string());
+            event.insert("event_type", pair_event.type_name());
+            event.insert("account", account.to_string());
+            events.push(event);
+        }
     };
     for event in events {
         let _: URef = storage::new_uref(event);
@@ -1,10 +1,16 @@
 #![allow(unused_parens)]
 #![allow(non_snake_case)]
+#![no_std]
 #![no_main]
+#![feature(alloc_error_handler, lang_items)]
 
 extern crate alloc;
 
-use alloc::{string::String, vec::Vec};
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec::Vec,
+};
 use core::convert::TryInto;
 use error::Error;
 
@@ -13,19 +19,72 @@ use contract::{
         runtime,
         storage::{self, create_contract_package_at_hash},
     },
+    ext_ffi,
     unwrap_or_revert::UnwrapOrRevert,
 };
 use types::{
     account::AccountHash,
-    bytesrepr::{FromBytes, ToBytes},
+    api_error,
+    bytesrepr::{Bytes, FromBytes, ToBytes},
     contracts::NamedKeys,
+    crypto, runtime_args,
     system::CallStackElement,
-    ApiError, CLTyped, CLValue, Key, URef, U256,
+    ApiError, CLTyped, CLValue, ContractPackageHash, Key, PublicKey, RuntimeArgs, Signature, URef,
+    U256,
 };
+#[cfg(target_arch = "wasm32")]
+mod allocator;
 pub mod entry_points;
 pub mod error;
 pub mod events;
+pub mod restriction;
 use events::ERC20Event;
+use restriction::{Restriction, NO_RESTRICTION};
+
+/// Keys that can be published in the `metadata` dictionary.
+const METADATA_KEYS: [&str; 3] = ["icon_uri", "website", "description"];
+
+/// Maximum length of the `name` property, in bytes.
+const MAX_NAME_LENGTH: usize = 64;
+/// Maximum length of the `symbol` property, in bytes.
+const MAX_SYMBOL_LENGTH: usize = 11;
+/// Maximum value of the `decimals` property.
+const MAX_DECIMALS: u8 = 18;
+/// Maximum length of a transfer memo, in bytes.
+const MAX_MEMO_LENGTH: usize = 256;
+/// A recurring allowance, stored as `((amount_per_period, period_ms), (period_start, collected))`
+/// where `collected` is the amount already pulled in the period starting at `period_start`.
+type RecurringAllowance = ((U256, u64), (u64, U256));
+
+/// Deadline of allowances that never expire.
+const NO_EXPIRY: u64 = u64::MAX;
+/// Expiry stored in the `allowlist` dictionary for addresses that are not on it.
+const NOT_ALLOWLISTED: u64 = 0;
+/// Prefix of the domain separator, so signatures for this token are not valid for other contracts.
+const DOMAIN_TAG: &[u8] = b"casper-erc20";
+/// Tag of the messages signed for `permit`.
+const PERMIT_TAG: &[u8] = b"permit";
+/// Tag of the messages signed for `transfer_with_authorization`.
+const TRANSFER_AUTHORIZATION_TAG: &[u8] = b"transfer_with_authorization";
+/// Tag of the messages signed for `cancel_authorization`.
+const CANCEL_AUTHORIZATION_TAG: &[u8] = b"cancel_authorization";
+/// Tag of the messages signed for `delegate_by_sig`.
+const DELEGATION_TAG: &[u8] = b"delegate";
+/// A checkpoint of the votes of a delegate, stored as `(timestamp, votes)`. Each one is a separate
+/// entry of `vote_checkpoints`, and `num_vote_checkpoints` counts them per delegate.
+type VoteCheckpoint = (u64, U256);
+/// A checkpoint of a value for a snapshot, stored as `(snapshot_id, value)`. Each one is a
+/// separate entry of `balance_snapshots` or `total_supply_snapshots`.
+type SnapshotCheckpoint = (u64, U256);
+/// Number of snapshot checkpoints of a series and the snapshot id of the latest one, stored in
+/// `balance_snapshot_heads` or `total_supply_snapshot_heads`.
+type SnapshotHead = (u32, u64);
+/// Series of the total supply in its snapshot checkpoints.
+const TOTAL_SUPPLY_SERIES: &str = "total_supply";
+/// Denominator of the flash loan fee, which is set in basis points.
+const FLASH_FEE_DENOMINATOR: u64 = 10_000;
+/// Suffixes of the named keys `call` puts under the installing account next to `token_name`.
+const INSTALL_KEY_SUFFIXES: [&str; 4] = ["", "_hash", "_package_hash", "_access_token"];
 
 /// # Purpose
 /// * Returns the `name` property.
@@ -87,6 +146,40 @@ pub extern "C" fn allowance() {
     ret(amount)
 }
 
+/// # Purpose
+/// * Returns the balances of several addresses in a single call.
+/// # Arguments
+/// * `addresses` - `Vec<Key>` -> Addresses that we are looking for their token balances.
+/// # Returns
+/// * `balances` - `Vec<U256>` -> The balance of each address, in the same order.
+#[no_mangle]
+pub extern "C" fn balances_of() {
+    let addresses: Vec<Key> = runtime::get_named_arg("addresses");
+    let balances_uref = balances_uref();
+    let balances: Vec<U256> = addresses
+        .iter()
+        .map(|address| _read_balance_from(balances_uref, &key_to_str(address)))
+        .collect();
+    ret(balances)
+}
+
+/// # Purpose
+/// * Returns several allowances in a single call.
+/// # Arguments
+/// * `pairs` - `Vec<(Key, Key)>` -> `(owner, spender)` pairs.
+/// # Returns
+/// * `amounts` - `Vec<U256>` -> The allowance of each pair, in the same order.
+#[no_mangle]
+pub extern "C" fn allowances_of() {
+    let pairs: Vec<(Key, Key)> = runtime::get_named_arg("pairs");
+    let allowances_uref = allowances_uref();
+    let amounts: Vec<U256> = pairs
+        .into_iter()
+        .map(|(owner, spender)| _read_allowance_from(allowances_uref, owner, spender))
+        .collect();
+    ret(amounts)
+}
+
 /// # Purpose
 /// * Grants an address the liberty to spend an amount of the caller's tokens.
 /// # Arguments
@@ -97,7 +190,248 @@ pub extern "C" fn approve() {
     let spender: Key = runtime::get_named_arg("spender");
     let amount: U256 = runtime::get_named_arg("amount");
 
-    _approve(get_caller(), spender, amount);
+    let owner = get_caller();
+    _approve(
+        allowances_uref(),
+        blacklist_uref(),
+        events::contract_package_hash(),
+        owner,
+        spender,
+        amount,
+        NO_EXPIRY,
+    );
+    _index_spender(owner, spender, amount);
+}
+
+/// # Purpose
+/// * Grants an address the liberty to spend an amount of the caller's tokens until a deadline.
+/// # Arguments
+/// * `spender` - `Key` -> Address of the spender.
+/// * `amount` - `U256` -> Amount of the allowance.
+/// * `expires_at` - `u64` -> Block time, in milliseconds, after which the allowance reads as zero.
+#[no_mangle]
+pub extern "C" fn approve_with_expiry() {
+    let spender: Key = runtime::get_named_arg("spender");
+    let amount: U256 = runtime::get_named_arg("amount");
+    let expires_at: u64 = runtime::get_named_arg("expires_at");
+
+    let owner = get_caller();
+    _approve(
+        allowances_uref(),
+        blacklist_uref(),
+        events::contract_package_hash(),
+        owner,
+        spender,
+        amount,
+        expires_at,
+    );
+    _index_spender(owner, spender, amount);
+}
+
+/// # Purpose
+/// * Grants an allowance on behalf of an account that signed it off-chain, so the owner does not
+///   have to send an `approve` deploy itself.
+/// # Arguments
+/// * `owner_public_key` - `PublicKey` -> Ed25519 or secp256k1 public key of the owner.
+/// * `spender` - `Key` -> Address of the spender.
+/// * `amount` - `U256` -> Amount of the allowance.
+/// * `deadline` - `u64` -> Block time, in milliseconds, after which the signature is rejected.
+/// * `signature` - `Bytes` -> 64 byte signature of the owner over the permit message, which is the
+///   blake2b hash of the domain separator, `"permit"` and the serialized
+///   `(owner_public_key, spender, amount, nonce, deadline)`.
+#[no_mangle]
+pub extern "C" fn permit() {
+    let owner_public_key: PublicKey = runtime::get_named_arg("owner_public_key");
+    let spender: Key = runtime::get_named_arg("spender");
+    let amount: U256 = runtime::get_named_arg("amount");
+    let deadline: u64 = runtime::get_named_arg("deadline");
+    let signature: Bytes = runtime::get_named_arg("signature");
+
+    if u64::from(runtime::get_blocktime()) > deadline {
+        runtime::revert(Error::SignatureExpired);
+    }
+
+    let owner = Key::Account(owner_public_key.to_account_hash());
+    let nonce = _use_nonce(owner);
+    let package = events::contract_package_hash();
+    let fields = (owner_public_key.clone(), spender, amount, nonce, deadline)
+        .to_bytes()
+        .unwrap_or_revert();
+    _check_signature(package, &owner_public_key, PERMIT_TAG, &fields, &signature);
+
+    _approve(
+        allowances_uref(),
+        blacklist_uref(),
+        package,
+        owner,
+        spender,
+        amount,
+        NO_EXPIRY,
+    );
+    _index_spender(owner, spender, amount);
+}
+
+/// # Purpose
+/// * Returns the nonce the next `permit` or `delegate_by_sig` signed by the given owner must use.
+/// # Arguments
+/// * `owner` - `Key` -> Address of the owner.
+/// # Returns
+/// * `nonce` - `u64` -> Number of signed messages of the owner used so far.
+#[no_mangle]
+pub extern "C" fn nonces() {
+    let owner: Key = runtime::get_named_arg("owner");
+    let nonce: u64 = get("nonces", &key_to_str(&owner));
+    ret(nonce)
+}
+
+/// # Purpose
+/// * Returns the domain separator prefixed to every message signed for this token.
+/// # Returns
+/// * `domain_separator` - `[u8; 32]` -> blake2b hash of `"casper-erc20"` and the contract
+///   package hash.
+#[no_mangle]
+pub extern "C" fn domain_separator() {
+    ret(_domain_separator(events::contract_package_hash()))
+}
+
+/// # Purpose
+/// * Lets an address pull up to an amount of the caller's tokens once per period, e.g. for
+///   subscriptions. Periods start at the block time of this call. An amount of zero revokes it.
+/// # Arguments
+/// * `spender` - `Key` -> Address of the spender.
+/// * `amount_per_period` - `U256` -> Amount that can be collected in each period.
+/// * `period_ms` - `u64` -> Length of a period, in milliseconds.
+#[no_mangle]
+pub extern "C" fn approve_recurring() {
+    let spender: Key = runtime::get_named_arg("spender");
+    let amount_per_period: U256 = runtime::get_named_arg("amount_per_period");
+    let period_ms: u64 = runtime::get_named_arg("period_ms");
+
+    if period_ms == 0 {
+        runtime::revert(Error::InvalidPeriod);
+    }
+
+    let owner = get_caller();
+    _check_keys_not_null(owner, spender);
+    let blacklist_uref = blacklist_uref();
+    _check_not_blacklisted(blacklist_uref, &key_to_str(&owner));
+    _check_not_blacklisted(blacklist_uref, &key_to_str(&spender));
+
+    let now = u64::from(runtime::get_blocktime());
+    write_recurring_allowance(
+        owner,
+        spender,
+        ((amount_per_period, period_ms), (now, U256::zero())),
+    );
+    _index_spender(owner, spender, amount_per_period);
+
+    events::emit(
+        events::contract_package_hash(),
+        &ERC20Event::RecurringApproval {
+            owner,
+            spender,
+            value: amount_per_period,
+            period: period_ms,
+        },
+    );
+}
+
+/// # Purpose
+/// * Returns how much of a recurring allowance can still be collected in the current period.
+/// # Arguments
+/// * `owner` - `Key` -> Address of the owner.
+/// * `spender` - `Key` -> Address of the spender.
+/// # Returns
+/// * `amount` - `U256` -> Amount still available in the current period.
+#[no_mangle]
+pub extern "C" fn recurring_allowance() {
+    let owner: Key = runtime::get_named_arg("owner");
+    let spender: Key = runtime::get_named_arg("spender");
+    let ((amount_per_period, _), (_, collected)) = read_recurring_allowance(owner, spender);
+    ret(amount_per_period.saturating_sub(collected))
+}
+
+/// # Purpose
+/// * Transfers an `amount` of tokens from `owner` to `recipient`, spending the caller's
+///   recurring allowance for the current period.
+/// # Arguments
+/// * `owner` - `Key` -> Address of the owner.
+/// * `recipient` - `Key` -> Address of the recipient.
+/// * `amount` - `U256` -> Amount of the tokens to be sent.
+#[no_mangle]
+pub extern "C" fn collect() {
+    let owner: Key = runtime::get_named_arg("owner");
+    let recipient: Key = runtime::get_named_arg("recipient");
+    let amount: U256 = runtime::get_named_arg("amount");
+
+    let spender = get_caller();
+    let ledger = Ledger::load();
+    _check_not_blacklisted(ledger.blacklist, &key_to_str(&spender));
+
+    let ((amount_per_period, period_ms), (period_start, collected)) =
+        read_recurring_allowance(owner, spender);
+    let new_collected = collected
+        .checked_add(amount)
+        .filter(|new_collected| *new_collected <= amount_per_period)
+        .ok_or(Error::InsufficientAllowance)
+        .unwrap_or_revert();
+
+    write_recurring_allowance(
+        owner,
+        spender,
+        (
+            (amount_per_period, period_ms),
+            (period_start, new_collected),
+        ),
+    );
+
+    _transfer(&ledger, owner, recipient, amount);
+}
+
+/// # Purpose
+/// * Returns every spender `owner` has approved since it last revoked all of its allowances.
+///   Spenders whose allowance has since dropped to zero may still be listed.
+/// # Arguments
+/// * `owner` - `Key` -> Address of the owner.
+/// # Returns
+/// * `spenders` - `Vec<Key>` -> Addresses of the spenders.
+#[no_mangle]
+pub extern "C" fn spenders_of() {
+    let owner: Key = runtime::get_named_arg("owner");
+    let spenders: Vec<Key> = get("spenders", &key_to_str(&owner));
+    ret(spenders)
+}
+
+/// # Purpose
+/// * Sets every allowance of the caller, including recurring ones, to zero. Works even if the
+///   caller or a spender is blacklisted.
+#[no_mangle]
+pub extern "C" fn revoke_all_allowances() {
+    let owner = get_caller();
+    let owner_item_key = key_to_str(&owner);
+    let spenders: Vec<Key> = get("spenders", &owner_item_key);
+
+    let allowances_uref = allowances_uref();
+    let package = events::contract_package_hash();
+    for spender in spenders {
+        // Written directly rather than through `_approve`, so the blacklist never keeps an
+        // approval alive.
+        _write_allowance_to(allowances_uref, owner, spender, U256::zero(), NO_EXPIRY);
+        events::emit(
+            package,
+            &ERC20Event::Approval {
+                owner,
+                spender,
+                value: U256::zero(),
+            },
+        );
+        let ((amount_per_period, _), _) = read_recurring_allowance(owner, spender);
+        if !amount_per_period.is_zero() {
+            write_recurring_allowance(owner, spender, Default::default());
+        }
+    }
+
+    set("spenders", &owner_item_key, Vec::<Key>::new());
 }
 
 /// # Purpose
@@ -110,7 +444,143 @@ pub extern "C" fn transfer() {
     let recipient: Key = runtime::get_named_arg("recipient");
     let amount: U256 = runtime::get_named_arg("amount");
 
-    _transfer(get_caller(), recipient, amount);
+    _transfer(&Ledger::load(), get_caller(), recipient, amount);
+}
+
+/// # Purpose
+/// * Transfers tokens on behalf of an account that signed the transfer off-chain, so a relayer
+///   can submit it. Any number of authorizations can be outstanding, each with its own nonce.
+/// # Arguments
+/// * `from_public_key` - `PublicKey` -> Ed25519 or secp256k1 public key of the holder.
+/// * `to` - `Key` -> Address of the recipient.
+/// * `amount` - `U256` -> Amount of the tokens to be sent.
+/// * `valid_after` - `u64` -> Block time, in milliseconds, before which the transfer is rejected.
+/// * `valid_before` - `u64` -> Block time, in milliseconds, after which the transfer is rejected.
+/// * `nonce` - `[u8; 32]` -> Random nonce chosen by the holder.
+/// * `signature` - `Bytes` -> 64 byte signature of the holder over the blake2b hash of the domain
+///   separator, `"transfer_with_authorization"` and the serialized
+///   `(from_public_key, to, amount, fee, valid_after, valid_before, nonce)`.
+/// * `fee` - `U256` -> Optional amount of the holder's tokens paid to the caller, zero if omitted.
+#[no_mangle]
+pub extern "C" fn transfer_with_authorization() {
+    let from_public_key: PublicKey = runtime::get_named_arg("from_public_key");
+    let to: Key = runtime::get_named_arg("to");
+    let amount: U256 = runtime::get_named_arg("amount");
+    let valid_after: u64 = runtime::get_named_arg("valid_after");
+    let valid_before: u64 = runtime::get_named_arg("valid_before");
+    let nonce: [u8; 32] = runtime::get_named_arg("nonce");
+    let signature: Bytes = runtime::get_named_arg("signature");
+    let fee: U256 = get_optional_named_arg("fee").unwrap_or_default();
+
+    let now = u64::from(runtime::get_blocktime());
+    if now <= valid_after {
+        runtime::revert(Error::AuthorizationNotYetValid);
+    }
+    if now >= valid_before {
+        runtime::revert(Error::SignatureExpired);
+    }
+
+    let from = Key::Account(from_public_key.to_account_hash());
+    let package = events::contract_package_hash();
+    let fields = (
+        from_public_key.clone(),
+        to,
+        amount,
+        fee,
+        valid_after,
+        valid_before,
+        nonce,
+    )
+        .to_bytes()
+        .unwrap_or_revert();
+    _check_signature(
+        package,
+        &from_public_key,
+        TRANSFER_AUTHORIZATION_TAG,
+        &fields,
+        &signature,
+    );
+    _use_authorization(
+        package,
+        from,
+        nonce,
+        ERC20Event::AuthorizationUsed {
+            authorizer: from,
+            nonce,
+        },
+    );
+
+    let ledger = Ledger::load();
+    _transfer(&ledger, from, to, amount);
+    if !fee.is_zero() {
+        _transfer(&ledger, from, get_caller(), fee);
+    }
+}
+
+/// # Purpose
+/// * Invalidates an authorization that has not been used yet.
+/// # Arguments
+/// * `from_public_key` - `PublicKey` -> Ed25519 or secp256k1 public key of the holder.
+/// * `nonce` - `[u8; 32]` -> Nonce of the authorization.
+/// * `signature` - `Bytes` -> 64 byte signature of the holder over the blake2b hash of the domain
+///   separator, `"cancel_authorization"` and the serialized `(from_public_key, nonce)`.
+#[no_mangle]
+pub extern "C" fn cancel_authorization() {
+    let from_public_key: PublicKey = runtime::get_named_arg("from_public_key");
+    let nonce: [u8; 32] = runtime::get_named_arg("nonce");
+    let signature: Bytes = runtime::get_named_arg("signature");
+
+    let from = Key::Account(from_public_key.to_account_hash());
+    let package = events::contract_package_hash();
+    let fields = (from_public_key.clone(), nonce)
+        .to_bytes()
+        .unwrap_or_revert();
+    _check_signature(
+        package,
+        &from_public_key,
+        CANCEL_AUTHORIZATION_TAG,
+        &fields,
+        &signature,
+    );
+    _use_authorization(
+        package,
+        from,
+        nonce,
+        ERC20Event::AuthorizationCanceled {
+            authorizer: from,
+            nonce,
+        },
+    );
+}
+
+/// # Purpose
+/// * Returns whether the given authorization nonce has been used or canceled.
+/// # Arguments
+/// * `authorizer` - `Key` -> Address of the holder.
+/// * `nonce` - `[u8; 32]` -> Nonce of the authorization.
+/// # Returns
+/// * `used` - `bool` -> Whether the nonce can no longer be used.
+#[no_mangle]
+pub extern "C" fn authorization_state() {
+    let authorizer: Key = runtime::get_named_arg("authorizer");
+    let nonce: [u8; 32] = runtime::get_named_arg("nonce");
+    let used: bool = get(
+        "authorization_states",
+        &_authorization_item_key(authorizer, nonce),
+    );
+    ret(used)
+}
+
+/// # Purpose
+/// * Transfers the caller's tokens to several recipients at once. The caller is debited once
+///   for the total, and the whole batch reverts if any leg fails.
+/// # Arguments
+/// * `recipients` - `Vec<(Key, U256)>` -> `(recipient, amount)` legs of the batch.
+#[no_mangle]
+pub extern "C" fn batch_transfer() {
+    let recipients: Vec<(Key, U256)> = runtime::get_named_arg("recipients");
+
+    _batch_transfer(&Ledger::load(), get_caller(), recipients);
 }
 
 /// # Purpose
@@ -125,120 +595,1124 @@ pub extern "C" fn transfer_from() {
     let recipient: Key = runtime::get_named_arg("recipient");
     let amount: U256 = runtime::get_named_arg("amount");
 
-    _transfer_from(owner, recipient, amount);
+    _transfer_from(
+        &Ledger::load(),
+        allowances_uref(),
+        get_caller(),
+        owner,
+        recipient,
+        amount,
+    );
 }
 
 /// # Purpose
-/// * Creates an `amount` of tokens for the given address.
+/// * Transfers tokens on behalf of several owners at once, spending the caller's allowance from
+///   each of them. The whole batch reverts if any item fails.
+/// # Arguments
+/// * `items` - `Vec<(Key, Key, U256)>` -> `(owner, recipient, amount)` items of the batch.
+#[no_mangle]
+pub extern "C" fn batch_transfer_from() {
+    let items: Vec<(Key, Key, U256)> = runtime::get_named_arg("items");
+
+    let ledger = Ledger::load();
+    let allowances_uref = allowances_uref();
+    let spender = get_caller();
+
+    for (owner, recipient, amount) in items {
+        _transfer_from(&ledger, allowances_uref, spender, owner, recipient, amount);
+    }
+}
+
+/// # Purpose
+/// * Transfers an amount of the caller's tokens to the given address, tagged with a payment
+///   reference. A `TransferWithMemo` event is emitted alongside the regular `Transfer` event.
+/// # Arguments
+/// * `recipient` - `Key` -> Address of the recipient.
+/// * `amount` - `U256` -> Amount of the tokens to be sent.
+/// * `memo` - `String` -> Payment reference, at most [`MAX_MEMO_LENGTH`] bytes long.
+#[no_mangle]
+pub extern "C" fn transfer_with_memo() {
+    let recipient: Key = runtime::get_named_arg("recipient");
+    let amount: U256 = runtime::get_named_arg("amount");
+    let memo: String = runtime::get_named_arg("memo");
+
+    _check_memo(&memo);
+
+    let sender = get_caller();
+    let ledger = Ledger::load();
+    _transfer(&ledger, sender, recipient, amount);
+
+    events::emit(
+        ledger.package,
+        &ERC20Event::TransferWithMemo {
+            from: sender,
+            to: recipient,
+            value: amount,
+            memo,
+        },
+    );
+}
+
+/// # Purpose
+/// * Transfers an `amount` of tokens from `owner` to `recipient`, tagged with a payment
+///   reference. A `TransferWithMemo` event is emitted alongside the regular `Transfer` event.
 /// # Arguments
 /// * `owner` - `Key` -> Address of the owner.
-/// * `amount` - `U256` -> Amount of the tokens to be created.
+/// * `recipient` - `Key` -> Address of the recipient.
+/// * `amount` - `U256` -> Amount of the tokens to be sent.
+/// * `memo` - `String` -> Payment reference, at most [`MAX_MEMO_LENGTH`] bytes long.
 #[no_mangle]
-pub extern "C" fn mint() {
+pub extern "C" fn transfer_from_with_memo() {
     let owner: Key = runtime::get_named_arg("owner");
+    let recipient: Key = runtime::get_named_arg("recipient");
     let amount: U256 = runtime::get_named_arg("amount");
+    let memo: String = runtime::get_named_arg("memo");
 
-    if (owner == Key::Hash([0u8; 32]) || owner == Key::Account(AccountHash::new([0u8; 32]))) {
-        runtime::revert(Error::CannotMintToZeroHash);
-    }
-
-    let total_supply = get_key::<U256>("total_supply");
+    _check_memo(&memo);
 
-    set_key(
-        "total_supply",
-        total_supply.checked_add(amount).unwrap_or_revert(),
+    let ledger = Ledger::load();
+    _transfer_from(
+        &ledger,
+        allowances_uref(),
+        get_caller(),
+        owner,
+        recipient,
+        amount,
     );
 
-    let balance = get::<U256>("balances", &key_to_str(&owner));
+    events::emit(
+        ledger.package,
+        &ERC20Event::TransferWithMemo {
+            from: owner,
+            to: recipient,
+            value: amount,
+            memo,
+        },
+    );
+}
 
-    set(
-        "balances",
-        &key_to_str(&owner),
-        balance.checked_add(amount).unwrap_or_revert(),
+/// # Purpose
+/// * Transfers an amount of the caller's tokens to a contract and then notifies it through its
+///   `on_transfer_received` entry point. Reverts if the contract rejects the tokens.
+/// # Arguments
+/// * `recipient_contract` - `Key` -> Contract package hash of the recipient.
+/// * `amount` - `U256` -> Amount of the tokens to be sent.
+/// * `data` - `Bytes` -> Arbitrary data forwarded to the recipient.
+#[no_mangle]
+pub extern "C" fn transfer_and_call() {
+    let recipient_contract: Key = runtime::get_named_arg("recipient_contract");
+    let amount: U256 = runtime::get_named_arg("amount");
+    let data: Bytes = runtime::get_named_arg("data");
+
+    _lock();
+
+    let recipient_package = _contract_package_hash_of(recipient_contract);
+    let sender = get_caller();
+
+    // The callback below proves the recipient handles tokens, registered or not.
+    _transfer_unchecked(&Ledger::load(), sender, recipient_contract, amount);
+
+    let accepted: bool = runtime::call_versioned_contract(
+        recipient_package,
+        None,
+        "on_transfer_received",
+        runtime_args! {
+            "operator" => sender,
+            "from" => sender,
+            "amount" => amount,
+            "data" => data,
+        },
     );
+    if !accepted {
+        runtime::revert(Error::CallbackRejected);
+    }
 
-    events::emit(&ERC20Event::Transfer {
-        from: Key::Hash([0u8; 32]),
-        to: owner,
-        value: amount,
-    });
+    _unlock();
 }
 
 /// # Purpose
-/// * Destroys an `amount` of tokens from the given address.
+/// * Lends freshly minted tokens to a contract for the duration of its `on_flash_loan` callback
+///   (ERC-3156). The contract must approve this token to take back the amount plus the fee,
+///   which is then burned, or sent to the fee recipient for the fee part.
 /// # Arguments
-/// * `owner` - `Key` -> Address of the owner.
-/// * `amount` - `U256` -> Amount of the tokens to be destroyed.
+/// * `receiver` - `Key` -> Contract package hash of the borrower.
+/// * `amount` - `U256` -> Amount of the tokens to lend.
+/// * `data` - `Bytes` -> Arbitrary data forwarded to the borrower.
 #[no_mangle]
-pub extern "C" fn burn() {
-    let owner: Key = runtime::get_named_arg("owner");
+pub extern "C" fn flash_loan() {
+    let receiver: Key = runtime::get_named_arg("receiver");
     let amount: U256 = runtime::get_named_arg("amount");
+    let data: Bytes = runtime::get_named_arg("data");
 
-    if (owner == Key::Hash([0u8; 32]) || owner == Key::Account(AccountHash::new([0u8; 32]))) {
-        runtime::revert(Error::CannotBurnFromZeroHash);
-    }
-
-    let balance = get::<U256>("balances", &key_to_str(&owner));
+    _lock();
 
-    if (balance < amount) {
-        runtime::revert(Error::BurnAmountExceedsBalance);
+    if amount > _max_flash_loan() {
+        runtime::revert(Error::FlashLoanTooLarge);
+    }
+    let receiver_package = _contract_package_hash_of(receiver);
+    let fee = _flash_fee(amount);
+
+    // Minting skips the transfer restrictions, so the borrower is checked like a recipient.
+    let ledger = Ledger::load();
+    _check_restriction(_recipient_restriction(&ledger, &key_to_str(&receiver)));
+    _mint(&ledger, receiver, amount);
+
+    let accepted: bool = runtime::call_versioned_contract(
+        receiver_package,
+        None,
+        "on_flash_loan",
+        runtime_args! {
+            "initiator" => get_caller(),
+            "amount" => amount,
+            "fee" => fee,
+            "data" => data,
+        },
+    );
+    if !accepted {
+        runtime::revert(Error::CallbackRejected);
     }
 
-    set(
-        "balances",
-        &key_to_str(&owner),
-        balance.checked_sub(amount).unwrap_or_revert(),
+    // Loaded after the callback, which may have changed the token's settings.
+    let ledger = Ledger::load();
+    let allowances_uref = allowances_uref();
+    let token = Key::from(ledger.package);
+    let repayment = amount
+        .checked_add(fee)
+        .ok_or(Error::Overflow)
+        .unwrap_or_revert();
+    let (allowance, expires_at) = _read_allowance_entry_from(allowances_uref, receiver, token);
+    let new_allowance = allowance
+        .checked_sub(repayment)
+        .ok_or(Error::InsufficientAllowance)
+        .unwrap_or_revert();
+    _approve(
+        allowances_uref,
+        ledger.blacklist,
+        ledger.package,
+        receiver,
+        token,
+        new_allowance,
+        expires_at,
     );
 
-    let total_supply = get_key::<U256>("total_supply");
+    match get_key::<Option<Key>>("flash_fee_recipient") {
+        Some(fee_recipient) if !fee.is_zero() => {
+            _burn(&ledger, receiver, amount);
+            _transfer(&ledger, receiver, fee_recipient, fee);
+        }
+        _ => _burn(&ledger, receiver, repayment),
+    }
 
-    set_key(
-        "total_supply",
-        total_supply.checked_sub(amount).unwrap_or_revert(),
-    );
+    _unlock();
+}
 
-    events::emit(&ERC20Event::Transfer {
-        from: owner,
-        to: Key::Hash([0u8; 32]),
-        value: amount,
-    });
+/// # Purpose
+/// * Returns the largest amount `flash_loan` can lend.
+/// # Returns
+/// * `max_flash_loan` - `U256` -> Amount that can still be minted.
+#[no_mangle]
+pub extern "C" fn max_flash_loan() {
+    ret(_max_flash_loan())
 }
 
+/// # Purpose
+/// * Returns the fee `flash_loan` charges for lending the given amount.
+/// # Arguments
+/// * `amount` - `U256` -> Amount of the tokens to lend.
+/// # Returns
+/// * `fee` - `U256` -> Fee owed on top of the amount.
 #[no_mangle]
-pub extern "C" fn call() {
-    let token_name: String = runtime::get_named_arg("token_name");
-    let token_symbol: String = runtime::get_named_arg("token_symbol");
-    let token_decimals: u8 = runtime::get_named_arg("token_decimals");
-    let token_total_supply: U256 = runtime::get_named_arg("token_total_supply");
+pub extern "C" fn flash_fee() {
+    let amount: U256 = runtime::get_named_arg("amount");
+    ret(_flash_fee(amount))
+}
 
-    let entry_points = entry_points::default();
+/// # Purpose
+/// * Sets the flash loan fee. Can only be called by the admin.
+/// # Arguments
+/// * `basis_points` - `u64` -> Fee in hundredths of a percent of the lent amount, at most
+///   10_000.
+#[no_mangle]
+pub extern "C" fn set_flash_fee() {
+    let basis_points: u64 = runtime::get_named_arg("basis_points");
 
-    let balances_seed_uref = storage::new_dictionary("balances").unwrap_or_revert();
+    _check_caller_is_admin();
 
-    storage::dictionary_put(
-        balances_seed_uref,
-        &key_to_str(&Key::Account(runtime::get_caller())),
-        token_total_supply,
+    if basis_points > FLASH_FEE_DENOMINATOR {
+        runtime::revert(Error::InvalidFlashFee);
+    }
+    set_key("flash_fee_basis_points", basis_points);
+}
+
+/// # Purpose
+/// * Sets the address flash loan fees are paid to. Fees are burned while no recipient is set.
+///   Can only be called by the admin.
+/// # Arguments
+/// * `recipient` - `Option<Key>` -> Address of the fee recipient, or `None` to burn the fees.
+#[no_mangle]
+pub extern "C" fn set_flash_fee_recipient() {
+    let recipient: Option<Key> = runtime::get_named_arg("recipient");
+
+    _check_caller_is_admin();
+
+    set_key("flash_fee_recipient", recipient);
+}
+
+/// # Purpose
+/// * Grants a contract the liberty to spend an amount of the caller's tokens and then notifies
+///   it through its `on_approval_received` entry point. Reverts if the contract rejects it.
+/// # Arguments
+/// * `spender_contract` - `Key` -> Contract package hash of the spender.
+/// * `amount` - `U256` -> Amount of the allowance.
+/// * `data` - `Bytes` -> Arbitrary data forwarded to the spender.
+#[no_mangle]
+pub extern "C" fn approve_and_call() {
+    let spender_contract: Key = runtime::get_named_arg("spender_contract");
+    let amount: U256 = runtime::get_named_arg("amount");
+    let data: Bytes = runtime::get_named_arg("data");
+
+    _lock();
+
+    let spender_package = _contract_package_hash_of(spender_contract);
+    let owner = get_caller();
+
+    _approve(
+        allowances_uref(),
+        blacklist_uref(),
+        events::contract_package_hash(),
+        owner,
+        spender_contract,
+        amount,
+        NO_EXPIRY,
+    );
+    _index_spender(owner, spender_contract, amount);
+
+    let accepted: bool = runtime::call_versioned_contract(
+        spender_package,
+        None,
+        "on_approval_received",
+        runtime_args! {
+            "owner" => owner,
+            "amount" => amount,
+            "data" => data,
+        },
+    );
+    if !accepted {
+        runtime::revert(Error::CallbackRejected);
+    }
+
+    _unlock();
+}
+
+/// # Purpose
+/// * Authorizes an address to move all of the caller's tokens.
+/// # Arguments
+/// * `operator` - `Key` -> Address of the operator.
+#[no_mangle]
+pub extern "C" fn authorize_operator() {
+    let operator: Key = runtime::get_named_arg("operator");
+    let holder = get_caller();
+
+    _check_keys_not_null(holder, operator);
+    let blacklist_uref = blacklist_uref();
+    _check_not_blacklisted(blacklist_uref, &key_to_str(&holder));
+    _check_not_blacklisted(blacklist_uref, &key_to_str(&operator));
+
+    set(
+        "operators",
+        &make_dictionary_item_key(holder, operator),
+        true,
+    );
+
+    events::emit(
+        events::contract_package_hash(),
+        &ERC20Event::AuthorizedOperator { operator, holder },
+    );
+}
+
+/// # Purpose
+/// * Revokes an operator previously authorized by the caller.
+/// # Arguments
+/// * `operator` - `Key` -> Address of the operator.
+#[no_mangle]
+pub extern "C" fn revoke_operator() {
+    let operator: Key = runtime::get_named_arg("operator");
+    let holder = get_caller();
+
+    set(
+        "operators",
+        &make_dictionary_item_key(holder, operator),
+        false,
+    );
+
+    events::emit(
+        events::contract_package_hash(),
+        &ERC20Event::RevokedOperator { operator, holder },
+    );
+}
+
+/// # Purpose
+/// * Returns whether `operator` may move all of the tokens of `holder`.
+/// # Arguments
+/// * `operator` - `Key` -> Address of the operator.
+/// * `holder` - `Key` -> Address of the holder.
+/// # Returns
+/// * `is_operator` - `bool` -> Whether `operator` is an operator for `holder`.
+#[no_mangle]
+pub extern "C" fn is_operator_for() {
+    let operator: Key = runtime::get_named_arg("operator");
+    let holder: Key = runtime::get_named_arg("holder");
+    ret(_is_operator_for(operator, holder))
+}
+
+/// # Purpose
+/// * Transfers an `amount` of tokens from `holder` to `recipient`, on behalf of `holder`.
+///   The caller must be an operator for `holder`.
+/// # Arguments
+/// * `holder` - `Key` -> Address of the holder.
+/// * `recipient` - `Key` -> Address of the recipient.
+/// * `amount` - `U256` -> Amount of the tokens to be sent.
+#[no_mangle]
+pub extern "C" fn operator_send() {
+    let holder: Key = runtime::get_named_arg("holder");
+    let recipient: Key = runtime::get_named_arg("recipient");
+    let amount: U256 = runtime::get_named_arg("amount");
+
+    let operator = get_caller();
+    if !_is_operator_for(operator, holder) {
+        runtime::revert(Error::NotAnOperator);
+    }
+    let ledger = Ledger::load();
+    _check_not_blacklisted(ledger.blacklist, &key_to_str(&operator));
+
+    _transfer(&ledger, holder, recipient, amount);
+}
+
+/// # Purpose
+/// * Registers the calling contract as a token receiver, so it can be sent tokens while safe
+///   transfers are enabled.
+/// * A contract cannot inspect the entry points of another one, so this is not checked: by
+///   registering, the contract promises it implements `on_transfer_received` and can move the
+///   tokens it holds. Plain transfers to it never call the hook.
+#[no_mangle]
+pub extern "C" fn register_receiver() {
+    let receiver = get_caller();
+    if !matches!(receiver, Key::Hash(_)) {
+        runtime::revert(Error::NotAContract);
+    }
+    set("receivers", &key_to_str(&receiver), true);
+}
+
+/// # Purpose
+/// * Removes the calling contract from the registered token receivers.
+#[no_mangle]
+pub extern "C" fn unregister_receiver() {
+    let receiver = get_caller();
+    if !matches!(receiver, Key::Hash(_)) {
+        runtime::revert(Error::NotAContract);
+    }
+    set("receivers", &key_to_str(&receiver), false);
+}
+
+/// # Purpose
+/// * Turns the safe transfer policy on or off. While it is on, tokens can only be sent to
+///   contracts through `transfer_and_call` or after they called `register_receiver`.
+///   Can only be called by the admin.
+/// # Arguments
+/// * `enabled` - `bool` -> Whether safe transfers are enforced.
+#[no_mangle]
+pub extern "C" fn set_safe_transfers() {
+    let enabled: bool = runtime::get_named_arg("enabled");
+
+    _check_caller_is_admin();
+
+    set_key("safe_transfers", enabled);
+}
+
+/// # Purpose
+/// * Turns the allowlist mode on or off. While it is on, both the sender and the recipient of a
+///   transfer must be on the allowlist. Can only be called by the admin.
+/// # Arguments
+/// * `enabled` - `bool` -> Whether the allowlist is enforced.
+#[no_mangle]
+pub extern "C" fn set_allowlist_required() {
+    let enabled: bool = runtime::get_named_arg("enabled");
+
+    _check_caller_is_admin();
+
+    set_key("allowlist_required", enabled);
+}
+
+/// # Purpose
+/// * Grants the KYC role, which manages the allowlist. Can only be called by the admin.
+/// # Arguments
+/// * `account` - `Key` -> Address to grant the role to.
+#[no_mangle]
+pub extern "C" fn grant_kyc_role() {
+    let account: Key = runtime::get_named_arg("account");
+
+    _check_caller_is_admin();
+
+    set("kyc_officers", &key_to_str(&account), true);
+}
+
+/// # Purpose
+/// * Revokes the KYC role. Can only be called by the admin.
+/// # Arguments
+/// * `account` - `Key` -> Address to revoke the role from.
+#[no_mangle]
+pub extern "C" fn revoke_kyc_role() {
+    let account: Key = runtime::get_named_arg("account");
+
+    _check_caller_is_admin();
+
+    set("kyc_officers", &key_to_str(&account), false);
+}
+
+/// # Purpose
+/// * Adds verified holders to the allowlist, or renews their entries.
+///   Can only be called by a holder of the KYC role.
+/// # Arguments
+/// * `accounts` - `Vec<(Key, Option<u64>)>` -> `(account, expires_at)` entries. An entry stays
+///   valid up to and including its `expires_at` block time; `None` never expires.
+#[no_mangle]
+pub extern "C" fn allowlist_add() {
+    let accounts: Vec<(Key, Option<u64>)> = runtime::get_named_arg("accounts");
+
+    _check_caller_is_kyc_officer();
+
+    let package = events::contract_package_hash();
+    for (account, expires_at) in accounts {
+        set(
+            "allowlist",
+            &key_to_str(&account),
+            expires_at.unwrap_or(NO_EXPIRY),
+        );
+        events::emit(
+            package,
+            &ERC20Event::AllowlistAdded {
+                account,
+                expires_at,
+            },
+        );
+    }
+}
+
+/// # Purpose
+/// * Removes holders from the allowlist. Can only be called by a holder of the KYC role.
+/// # Arguments
+/// * `accounts` - `Vec<Key>` -> Addresses to remove.
+#[no_mangle]
+pub extern "C" fn allowlist_remove() {
+    let accounts: Vec<Key> = runtime::get_named_arg("accounts");
+
+    _check_caller_is_kyc_officer();
+
+    let package = events::contract_package_hash();
+    for account in accounts {
+        set("allowlist", &key_to_str(&account), NOT_ALLOWLISTED);
+        events::emit(package, &ERC20Event::AllowlistRemoved { account });
+    }
+}
+
+/// # Purpose
+/// * Returns whether the given address is on the allowlist and its entry has not expired.
+/// # Arguments
+/// * `account` - `Key` -> Address to look up.
+/// # Returns
+/// * `allowlisted` - `bool` -> Whether the address is allowlisted.
+#[no_mangle]
+pub extern "C" fn is_allowlisted() {
+    let account: Key = runtime::get_named_arg("account");
+    ret(_is_allowlisted(
+        _get_uref("allowlist"),
+        &key_to_str(&account),
+    ))
+}
+
+/// # Purpose
+/// * Freezes an address: it can no longer send, receive, approve or spend tokens.
+///   Can only be called by the admin.
+/// # Arguments
+/// * `account` - `Key` -> Address to freeze.
+#[no_mangle]
+pub extern "C" fn blacklist() {
+    let account: Key = runtime::get_named_arg("account");
+
+    _check_caller_is_admin();
+
+    set("blacklist", &key_to_str(&account), true);
+    events::emit(
+        events::contract_package_hash(),
+        &ERC20Event::Blacklisted { account },
+    );
+}
+
+/// # Purpose
+/// * Unfreezes an address frozen by `blacklist`. Can only be called by the admin.
+/// # Arguments
+/// * `account` - `Key` -> Address to unfreeze.
+#[no_mangle]
+pub extern "C" fn unblacklist() {
+    let account: Key = runtime::get_named_arg("account");
+
+    _check_caller_is_admin();
+
+    set("blacklist", &key_to_str(&account), false);
+    events::emit(
+        events::contract_package_hash(),
+        &ERC20Event::Unblacklisted { account },
+    );
+}
+
+/// # Purpose
+/// * Returns whether the given address is frozen.
+/// # Arguments
+/// * `account` - `Key` -> Address to look up.
+/// # Returns
+/// * `blacklisted` - `bool` -> Whether the address is frozen.
+#[no_mangle]
+pub extern "C" fn is_blacklisted() {
+    let account: Key = runtime::get_named_arg("account");
+    ret(_is_blacklisted(blacklist_uref(), &key_to_str(&account)))
+}
+
+/// # Purpose
+/// * Tells whether `transfer` would reject the given transfer, and why (ERC-1404).
+/// # Arguments
+/// * `from` - `Key` -> Address the tokens would be moved from.
+/// * `to` - `Key` -> Address the tokens would be moved to.
+/// * `amount` - `U256` -> Amount of the tokens to be moved.
+/// # Returns
+/// * `restriction_code` - `u8` -> `0` if the transfer would go through, otherwise the code of the
+///   first check it fails. See `message_for_transfer_restriction`.
+#[no_mangle]
+pub extern "C" fn detect_transfer_restriction() {
+    let from: Key = runtime::get_named_arg("from");
+    let to: Key = runtime::get_named_arg("to");
+    let amount: U256 = runtime::get_named_arg("amount");
+
+    let restriction = _transfer_restriction(&Ledger::load(), from, to, amount, true);
+    ret(restriction.map_or(NO_RESTRICTION, Restriction::code))
+}
+
+/// # Purpose
+/// * Returns a human-readable explanation of a code returned by `detect_transfer_restriction`.
+/// # Arguments
+/// * `restriction_code` - `u8` -> Code to explain.
+/// # Returns
+/// * `message` - `String` -> Explanation of the code.
+#[no_mangle]
+pub extern "C" fn message_for_transfer_restriction() {
+    let restriction_code: u8 = runtime::get_named_arg("restriction_code");
+
+    let message = if restriction_code == NO_RESTRICTION {
+        "No restriction"
+    } else {
+        Restriction::from_code(restriction_code)
+            .map_or("Unknown restriction code", Restriction::message)
+    };
+    ret(String::from(message))
+}
+
+/// # Purpose
+/// * Returns the published token metadata, such as `icon_uri`, `website` and `description`.
+/// # Returns
+/// * `metadata` - `BTreeMap<String, String>` -> The metadata entries that have been set.
+#[no_mangle]
+pub extern "C" fn token_metadata() {
+    ret(read_metadata())
+}
+
+/// # Purpose
+/// * Returns everything needed to render the token in a single call.
+/// # Returns
+/// * `info` - `((String, String, u8), U256, BTreeMap<String, String>)` -> `name`, `symbol` and
+///   `decimals`, followed by `total_supply` and the published token metadata.
+#[no_mangle]
+pub extern "C" fn token_info() {
+    let name: String = get_key("name");
+    let symbol: String = get_key("symbol");
+    let decimals: u8 = get_key("decimals");
+    let total_supply: U256 = get_key("total_supply");
+    ret(((name, symbol, decimals), total_supply, read_metadata()))
+}
+
+/// # Purpose
+/// * Sets a token metadata entry. Can only be called by the admin.
+/// # Arguments
+/// * `key` - `String` -> One of `icon_uri`, `website` or `description`.
+/// * `value` - `String` -> The new value of the entry.
+#[no_mangle]
+pub extern "C" fn set_metadata() {
+    let key: String = runtime::get_named_arg("key");
+    let value: String = runtime::get_named_arg("value");
+
+    _check_caller_is_admin();
+
+    if !METADATA_KEYS.contains(&key.as_str()) {
+        runtime::revert(Error::UnknownMetadataKey);
+    }
+
+    storage::dictionary_put(metadata_uref(), &key, value.clone());
+
+    events::emit(
+        events::contract_package_hash(),
+        &ERC20Event::MetadataUpdated { key, value },
+    );
+}
+
+/// # Purpose
+/// * Changes the `name` property. Can only be called by the admin.
+/// # Arguments
+/// * `name` - `String` -> The new name of the token.
+#[no_mangle]
+pub extern "C" fn set_name() {
+    let name: String = runtime::get_named_arg("name");
+
+    _check_caller_is_admin();
+    _check_token_name(&name);
+
+    set_key("name", name.clone());
+
+    events::emit(
+        events::contract_package_hash(),
+        &ERC20Event::MetadataUpdated {
+            key: "name".to_string(),
+            value: name,
+        },
+    );
+}
+
+/// # Purpose
+/// * Changes the `symbol` property. Can only be called by the admin.
+/// # Arguments
+/// * `symbol` - `String` -> The new symbol of the token.
+#[no_mangle]
+pub extern "C" fn set_symbol() {
+    let symbol: String = runtime::get_named_arg("symbol");
+
+    _check_caller_is_admin();
+    _check_token_symbol(&symbol);
+
+    set_key("symbol", symbol.clone());
+
+    events::emit(
+        events::contract_package_hash(),
+        &ERC20Event::MetadataUpdated {
+            key: "symbol".to_string(),
+            value: symbol,
+        },
+    );
+}
+
+/// # Purpose
+/// * Delegates the voting power of the caller's tokens to the given address. Tokens only count
+///   as votes once their holder delegated them, possibly to itself.
+/// # Arguments
+/// * `delegatee` - `Key` -> Address receiving the votes.
+#[no_mangle]
+pub extern "C" fn delegate() {
+    let delegatee: Key = runtime::get_named_arg("delegatee");
+
+    _delegate(events::contract_package_hash(), get_caller(), delegatee);
+}
+
+/// # Purpose
+/// * Delegates the voting power of an account that signed the delegation off-chain.
+/// # Arguments
+/// * `delegator_public_key` - `PublicKey` -> Ed25519 or secp256k1 public key of the delegator.
+/// * `delegatee` - `Key` -> Address receiving the votes.
+/// * `expiry` - `u64` -> Block time, in milliseconds, after which the signature is rejected.
+/// * `signature` - `Bytes` -> 64 byte signature of the delegator over the blake2b hash of the
+///   domain separator, `"delegate"` and the serialized
+///   `(delegator_public_key, delegatee, nonce, expiry)`.
+#[no_mangle]
+pub extern "C" fn delegate_by_sig() {
+    let delegator_public_key: PublicKey = runtime::get_named_arg("delegator_public_key");
+    let delegatee: Key = runtime::get_named_arg("delegatee");
+    let expiry: u64 = runtime::get_named_arg("expiry");
+    let signature: Bytes = runtime::get_named_arg("signature");
+
+    if u64::from(runtime::get_blocktime()) > expiry {
+        runtime::revert(Error::SignatureExpired);
+    }
+
+    let delegator = Key::Account(delegator_public_key.to_account_hash());
+    let nonce = _use_nonce(delegator);
+    let package = events::contract_package_hash();
+    let fields = (delegator_public_key.clone(), delegatee, nonce, expiry)
+        .to_bytes()
+        .unwrap_or_revert();
+    _check_signature(
+        package,
+        &delegator_public_key,
+        DELEGATION_TAG,
+        &fields,
+        &signature,
+    );
+
+    _delegate(package, delegator, delegatee);
+}
+
+/// # Purpose
+/// * Returns the address the given account delegates its votes to.
+/// # Arguments
+/// * `account` - `Key` -> Address of the delegator.
+/// # Returns
+/// * `delegatee` - `Option<Key>` -> Address of the delegate, if any.
+#[no_mangle]
+pub extern "C" fn delegates() {
+    let account: Key = runtime::get_named_arg("account");
+    ret(_delegate_of(_get_uref("delegates"), &key_to_str(&account)))
+}
+
+/// # Purpose
+/// * Returns the current votes of the given account.
+/// # Arguments
+/// * `account` - `Key` -> Address of the delegate.
+/// # Returns
+/// * `votes` - `U256` -> Votes delegated to the account.
+#[no_mangle]
+pub extern "C" fn get_votes() {
+    let account: Key = runtime::get_named_arg("account");
+    ret(_current_votes(VoteCheckpoints::load(), account))
+}
+
+/// # Purpose
+/// * Returns the votes of the given account at a past block time.
+/// # Arguments
+/// * `account` - `Key` -> Address of the delegate.
+/// * `timestamp` - `u64` -> Block time, in milliseconds, before the current one.
+/// # Returns
+/// * `votes` - `U256` -> Votes delegated to the account at the end of `timestamp`.
+#[no_mangle]
+pub extern "C" fn get_past_votes() {
+    let account: Key = runtime::get_named_arg("account");
+    let timestamp: u64 = runtime::get_named_arg("timestamp");
+
+    if timestamp >= u64::from(runtime::get_blocktime()) {
+        runtime::revert(Error::FutureLookup);
+    }
+
+    // Number of checkpoints taken at or before `timestamp`, found by binary search.
+    let checkpoints = VoteCheckpoints::load();
+    let (mut low, mut high) = (0, _num_vote_checkpoints(checkpoints, account));
+    while low < high {
+        let middle = low + (high - low) / 2;
+        let (checkpoint_time, _) = _vote_checkpoint(checkpoints, account, middle);
+        if checkpoint_time <= timestamp {
+            low = middle + 1;
+        } else {
+            high = middle;
+        }
+    }
+    let votes = match low {
+        0 => U256::zero(),
+        _ => _vote_checkpoint(checkpoints, account, low - 1).1,
+    };
+    ret(votes)
+}
+
+/// # Purpose
+/// * Takes a snapshot of all balances and of the total supply. Only the admin may call it.
+/// # Returns
+/// * `id` - `u64` -> Id of the new snapshot, starting at 1.
+#[no_mangle]
+pub extern "C" fn snapshot() {
+    _check_caller_is_admin();
+
+    let id = _current_snapshot_id() + 1;
+    set_key("snapshot_id", id);
+    events::emit(
+        events::contract_package_hash(),
+        &ERC20Event::Snapshot { id },
+    );
+    ret(id)
+}
+
+/// # Purpose
+/// * Returns the balance of the given address when the given snapshot was taken.
+/// # Arguments
+/// * `address` - `Key` -> Address of the holder.
+/// * `id` - `u64` -> Id of the snapshot.
+/// # Returns
+/// * `balance` - `U256` -> Balance of the holder at the snapshot.
+#[no_mangle]
+pub extern "C" fn balance_of_at() {
+    let address: Key = runtime::get_named_arg("address");
+    let id: u64 = runtime::get_named_arg("id");
+
+    _check_snapshot_id(id);
+    let item_key = key_to_str(&address);
+    let balance = _value_at(SnapshotCheckpoints::balances(), &item_key, id)
+        .unwrap_or_else(|| _read_balance_from(balances_uref(), &item_key));
+    ret(balance)
+}
+
+/// # Purpose
+/// * Returns the total supply when the given snapshot was taken.
+/// # Arguments
+/// * `id` - `u64` -> Id of the snapshot.
+/// # Returns
+/// * `total_supply` - `U256` -> Total supply at the snapshot.
+#[no_mangle]
+pub extern "C" fn total_supply_at() {
+    let id: u64 = runtime::get_named_arg("id");
+
+    _check_snapshot_id(id);
+    let total_supply = _value_at(SnapshotCheckpoints::total_supply(), TOTAL_SUPPLY_SERIES, id)
+        .unwrap_or_else(|| get_key("total_supply"));
+    ret(total_supply)
+}
+
+/// # Purpose
+/// * Creates an `amount` of tokens for the given address.
+/// # Arguments
+/// * `owner` - `Key` -> Address of the owner.
+/// * `amount` - `U256` -> Amount of the tokens to be created.
+#[no_mangle]
+pub extern "C" fn mint() {
+    let owner: Key = runtime::get_named_arg("owner");
+    let amount: U256 = runtime::get_named_arg("amount");
+
+    if (owner == Key::Hash([0u8; 32]) || owner == Key::Account(AccountHash::new([0u8; 32]))) {
+        runtime::revert(Error::CannotMintToZeroHash);
+    }
+
+    let ledger = Ledger::load();
+    let total_supply = get_key::<U256>("total_supply");
+    _checkpoint_total_supply(&ledger, total_supply);
+
+    set_key(
+        "total_supply",
+        total_supply.checked_add(amount).unwrap_or_revert(),
+    );
+
+    let owner_item_key = key_to_str(&owner);
+    let balance = _read_balance_from(ledger.balances, &owner_item_key);
+    _checkpoint_balance(&ledger, &owner_item_key, balance);
+
+    _write_balance_to(
+        ledger.balances,
+        &owner_item_key,
+        balance.checked_add(amount).unwrap_or_revert(),
+    );
+
+    _move_voting_power(
+        ledger.package,
+        None,
+        _delegate_of(ledger.delegates, &owner_item_key),
+        amount,
+    );
+
+    events::emit(
+        ledger.package,
+        &ERC20Event::Transfer {
+            from: Key::Hash([0u8; 32]),
+            to: owner,
+            value: amount,
+        },
+    );
+}
+
+/// # Purpose
+/// * Destroys an `amount` of tokens from the given address.
+/// # Arguments
+/// * `owner` - `Key` -> Address of the owner.
+/// * `amount` - `U256` -> Amount of the tokens to be destroyed.
+#[no_mangle]
+pub extern "C" fn burn() {
+    let owner: Key = runtime::get_named_arg("owner");
+    let amount: U256 = runtime::get_named_arg("amount");
+
+    if (owner == Key::Hash([0u8; 32]) || owner == Key::Account(AccountHash::new([0u8; 32]))) {
+        runtime::revert(Error::CannotBurnFromZeroHash);
+    }
+
+    let ledger = Ledger::load();
+    let owner_item_key = key_to_str(&owner);
+    let balance = _read_balance_from(ledger.balances, &owner_item_key);
+
+    if (balance < amount) {
+        runtime::revert(Error::BurnAmountExceedsBalance);
+    }
+    _checkpoint_balance(&ledger, &owner_item_key, balance);
+
+    _write_balance_to(
+        ledger.balances,
+        &owner_item_key,
+        balance.checked_sub(amount).unwrap_or_revert(),
+    );
+
+    let total_supply = get_key::<U256>("total_supply");
+    _checkpoint_total_supply(&ledger, total_supply);
+
+    set_key(
+        "total_supply",
+        total_supply.checked_sub(amount).unwrap_or_revert(),
+    );
+
+    _move_voting_power(
+        ledger.package,
+        _delegate_of(ledger.delegates, &owner_item_key),
+        None,
+        amount,
+    );
+
+    events::emit(
+        ledger.package,
+        &ERC20Event::Transfer {
+            from: owner,
+            to: Key::Hash([0u8; 32]),
+            value: amount,
+        },
+    );
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let token_name: String = runtime::get_named_arg("token_name");
+    let token_symbol: String = runtime::get_named_arg("token_symbol");
+    let token_decimals: u8 = runtime::get_named_arg("token_decimals");
+    let token_total_supply: U256 = runtime::get_named_arg("token_total_supply");
+    let safe_transfers: bool = get_optional_named_arg("safe_transfers").unwrap_or_default();
+    let allowlist_required: bool = get_optional_named_arg("allowlist_required").unwrap_or_default();
+
+    _check_token_name(&token_name);
+    _check_token_symbol(&token_symbol);
+    _check_token_decimals(token_decimals);
+    for suffix in INSTALL_KEY_SUFFIXES.iter() {
+        if runtime::has_key(&[token_name.as_str(), *suffix].concat()) {
+            runtime::revert(Error::TokenNameAlreadyInUse);
+        }
+    }
+
+    let entry_points = entry_points::default();
+
+    let balances_seed_uref = storage::new_dictionary("balances").unwrap_or_revert();
+
+    storage::dictionary_put(
+        balances_seed_uref,
+        &key_to_str(&Key::Account(runtime::get_caller())),
+        token_total_supply,
+    );
+
+    let allowances_seed_uref = storage::new_dictionary("allowances").unwrap_or_revert();
+    let metadata_seed_uref = storage::new_dictionary("metadata").unwrap_or_revert();
+    let receivers_seed_uref = storage::new_dictionary("receivers").unwrap_or_revert();
+    let operators_seed_uref = storage::new_dictionary("operators").unwrap_or_revert();
+    let recurring_allowances_seed_uref =
+        storage::new_dictionary("recurring_allowances").unwrap_or_revert();
+    let spenders_seed_uref = storage::new_dictionary("spenders").unwrap_or_revert();
+    let nonces_seed_uref = storage::new_dictionary("nonces").unwrap_or_revert();
+    let authorization_states_seed_uref =
+        storage::new_dictionary("authorization_states").unwrap_or_revert();
+    let balance_snapshots_seed_uref =
+        storage::new_dictionary("balance_snapshots").unwrap_or_revert();
+    let balance_snapshot_heads_seed_uref =
+        storage::new_dictionary("balance_snapshot_heads").unwrap_or_revert();
+    let total_supply_snapshots_seed_uref =
+        storage::new_dictionary("total_supply_snapshots").unwrap_or_revert();
+    let total_supply_snapshot_heads_seed_uref =
+        storage::new_dictionary("total_supply_snapshot_heads").unwrap_or_revert();
+    let delegates_seed_uref = storage::new_dictionary("delegates").unwrap_or_revert();
+    let blacklist_seed_uref = storage::new_dictionary("blacklist").unwrap_or_revert();
+    let allowlist_seed_uref = storage::new_dictionary("allowlist").unwrap_or_revert();
+    let kyc_officers_seed_uref = storage::new_dictionary("kyc_officers").unwrap_or_revert();
+
+    storage::dictionary_put(
+        kyc_officers_seed_uref,
+        &key_to_str(&Key::Account(runtime::get_caller())),
+        true,
+    );
+
+    let vote_checkpoints_seed_uref = storage::new_dictionary("vote_checkpoints").unwrap_or_revert();
+    let num_vote_checkpoints_seed_uref =
+        storage::new_dictionary("num_vote_checkpoints").unwrap_or_revert();
+    let mut named_keys = NamedKeys::new();
+
+    named_keys.insert(
+        "name".to_string(),
+        storage::new_uref(token_name.clone()).into(),
+    );
+    named_keys.insert("symbol".to_string(), storage::new_uref(token_symbol).into());
+    named_keys.insert(
+        "decimals".to_string(),
+        storage::new_uref(token_decimals).into(),
+    );
+    named_keys.insert(
+        "total_supply".to_string(),
+        storage::new_uref(token_total_supply).into(),
+    );
+    named_keys.insert("balances".to_string(), balances_seed_uref.into());
+    named_keys.insert("allowances".to_string(), allowances_seed_uref.into());
+    named_keys.insert("metadata".to_string(), metadata_seed_uref.into());
+    named_keys.insert("receivers".to_string(), receivers_seed_uref.into());
+    named_keys.insert("operators".to_string(), operators_seed_uref.into());
+    named_keys.insert(
+        "recurring_allowances".to_string(),
+        recurring_allowances_seed_uref.into(),
+    );
+    named_keys.insert("spenders".to_string(), spenders_seed_uref.into());
+    named_keys.insert("nonces".to_string(), nonces_seed_uref.into());
+    named_keys.insert(
+        "authorization_states".to_string(),
+        authorization_states_seed_uref.into(),
+    );
+    named_keys.insert(
+        "balance_snapshots".to_string(),
+        balance_snapshots_seed_uref.into(),
+    );
+    named_keys.insert(
+        "balance_snapshot_heads".to_string(),
+        balance_snapshot_heads_seed_uref.into(),
+    );
+    named_keys.insert(
+        "total_supply_snapshots".to_string(),
+        total_supply_snapshots_seed_uref.into(),
+    );
+    named_keys.insert(
+        "total_supply_snapshot_heads".to_string(),
+        total_supply_snapshot_heads_seed_uref.into(),
+    );
+    named_keys.insert("snapshot_id".to_string(), storage::new_uref(0u64).into());
+    named_keys.insert(
+        "flash_fee_basis_points".to_string(),
+        storage::new_uref(0u64).into(),
     );
-
-    let allowances_seed_uref = storage::new_dictionary("allowances").unwrap_or_revert();
-    let mut named_keys = NamedKeys::new();
-
     named_keys.insert(
-        "name".to_string(),
-        storage::new_uref(token_name.clone()).into(),
+        "flash_fee_recipient".to_string(),
+        storage::new_uref(Option::<Key>::None).into(),
     );
-    named_keys.insert("symbol".to_string(), storage::new_uref(token_symbol).into());
+    named_keys.insert("delegates".to_string(), delegates_seed_uref.into());
+    named_keys.insert("blacklist".to_string(), blacklist_seed_uref.into());
+    named_keys.insert("allowlist".to_string(), allowlist_seed_uref.into());
+    named_keys.insert("kyc_officers".to_string(), kyc_officers_seed_uref.into());
     named_keys.insert(
-        "decimals".to_string(),
-        storage::new_uref(token_decimals).into(),
+        "vote_checkpoints".to_string(),
+        vote_checkpoints_seed_uref.into(),
     );
     named_keys.insert(
-        "total_supply".to_string(),
-        storage::new_uref(token_total_supply).into(),
+        "num_vote_checkpoints".to_string(),
+        num_vote_checkpoints_seed_uref.into(),
+    );
+    named_keys.insert(
+        "safe_transfers".to_string(),
+        storage::new_uref(safe_transfers).into(),
+    );
+    named_keys.insert(
+        "allowlist_required".to_string(),
+        storage::new_uref(allowlist_required).into(),
+    );
+    named_keys.insert(
+        "reentrancy_lock".to_string(),
+        storage::new_uref(false).into(),
+    );
+    named_keys.insert(
+        "admin".to_string(),
+        storage::new_uref(Key::Account(runtime::get_caller())).into(),
     );
-    named_keys.insert("balances".to_string(), balances_seed_uref.into());
-    named_keys.insert("allowances".to_string(), allowances_seed_uref.into());
 
     let (contract_package_hash, access_uref) = create_contract_package_at_hash();
     named_keys.insert(
@@ -264,113 +1738,485 @@ pub extern "C" fn call() {
     );
 }
 
-fn _transfer(sender: Key, recipient: Key, amount: U256) {
-    _check_keys_not_null(sender, recipient);
-
-    let new_sender_balance: U256 = get::<U256>("balances", &key_to_str(&sender))
-        .checked_sub(amount)
-        .ok_or(Error::InsufficientBalance)
-        .unwrap_or_revert();
+fn _transfer(ledger: &Ledger, sender: Key, recipient: Key, amount: U256) {
+    _move_tokens(ledger, sender, recipient, amount, true);
+}
 
-    set("balances", &key_to_str(&sender), new_sender_balance);
+/// Moves tokens like [`_transfer`], without applying the safe transfer policy to `recipient`.
+fn _transfer_unchecked(ledger: &Ledger, sender: Key, recipient: Key, amount: U256) {
+    _move_tokens(ledger, sender, recipient, amount, false);
+}
 
-    let new_recipient_balance: U256 = get::<U256>("balances", &key_to_str(&recipient))
+/// Moves `amount` tokens from `sender` to `recipient`, reverting with the restriction
+/// [`_transfer_restriction`] finds, if any.
+fn _move_tokens(ledger: &Ledger, sender: Key, recipient: Key, amount: U256, check_receiver: bool) {
+    _check_restriction(_transfer_restriction(
+        ledger,
+        sender,
+        recipient,
+        amount,
+        check_receiver,
+    ));
+
+    let sender_item_key = key_to_str(&sender);
+    let recipient_item_key = key_to_str(&recipient);
+
+    // The sender balance covers `amount`, see `Restriction::InsufficientBalance`.
+    let sender_balance = _read_balance_from(ledger.balances, &sender_item_key);
+    _checkpoint_balance(ledger, &sender_item_key, sender_balance);
+    _write_balance_to(ledger.balances, &sender_item_key, sender_balance - amount);
+
+    let recipient_balance = _read_balance_from(ledger.balances, &recipient_item_key);
+    let new_recipient_balance: U256 = recipient_balance
         .checked_add(amount)
         .ok_or(Error::Overflow)
         .unwrap_or_revert();
 
-    set("balances", &key_to_str(&recipient), new_recipient_balance);
+    _checkpoint_balance(ledger, &recipient_item_key, recipient_balance);
+    _write_balance_to(ledger.balances, &recipient_item_key, new_recipient_balance);
+
+    _move_voting_power(
+        ledger.package,
+        _delegate_of(ledger.delegates, &sender_item_key),
+        _delegate_of(ledger.delegates, &recipient_item_key),
+        amount,
+    );
+
+    events::emit(
+        ledger.package,
+        &ERC20Event::Transfer {
+            from: sender,
+            to: recipient,
+            value: amount,
+        },
+    );
+}
+
+/// Moves tokens from `sender` to each of `recipients`.
+///
+/// Every leg is checked in the order of [`_transfer_restriction`] before any tokens move, with
+/// the balance checked once against the total.
+fn _batch_transfer(ledger: &Ledger, sender: Key, recipients: Vec<(Key, U256)>) {
+    let total: U256 = recipients
+        .iter()
+        .try_fold(U256::zero(), |total, (_, amount)| {
+            total.checked_add(*amount)
+        })
+        .ok_or(Error::Overflow)
+        .unwrap_or_revert();
 
-    events::emit(&ERC20Event::Transfer {
-        from: sender,
-        to: recipient,
-        value: amount,
-    });
+    for (recipient, _) in &recipients {
+        _check_restriction(_route_restriction(ledger, sender, *recipient, true));
+    }
+    _check_restriction(_balance_restriction(ledger, sender, total));
+
+    let sender_item_key = key_to_str(&sender);
+    let sender_balance = _read_balance_from(ledger.balances, &sender_item_key);
+    _checkpoint_balance(ledger, &sender_item_key, sender_balance);
+    _write_balance_to(ledger.balances, &sender_item_key, sender_balance - total);
+
+    let sender_delegate = _delegate_of(ledger.delegates, &sender_item_key);
+    for (recipient, amount) in recipients {
+        let recipient_item_key = key_to_str(&recipient);
+        let recipient_balance = _read_balance_from(ledger.balances, &recipient_item_key);
+        let new_recipient_balance: U256 = recipient_balance
+            .checked_add(amount)
+            .ok_or(Error::Overflow)
+            .unwrap_or_revert();
+
+        _checkpoint_balance(ledger, &recipient_item_key, recipient_balance);
+        _write_balance_to(ledger.balances, &recipient_item_key, new_recipient_balance);
+
+        _move_voting_power(
+            ledger.package,
+            sender_delegate,
+            _delegate_of(ledger.delegates, &recipient_item_key),
+            amount,
+        );
+
+        events::emit(
+            ledger.package,
+            &ERC20Event::Transfer {
+                from: sender,
+                to: recipient,
+                value: amount,
+            },
+        );
+    }
 }
 
-fn _transfer_from(owner: Key, recipient: Key, amount: U256) {
+fn _transfer_from(
+    ledger: &Ledger,
+    allowances_uref: URef,
+    spender: Key,
+    owner: Key,
+    recipient: Key,
+    amount: U256,
+) {
     _check_keys_not_null(owner, recipient);
 
-    let spender_allowance = read_allowance(owner, get_caller());
+    let (spender_allowance, expires_at) =
+        _read_allowance_entry_from(allowances_uref, owner, spender);
     let new_spender_allowance = spender_allowance
         .checked_sub(amount)
         .ok_or(Error::InsufficientAllowance)
         .unwrap_or_revert();
 
-    _transfer(owner, recipient, amount);
+    _transfer(ledger, owner, recipient, amount);
 
-    _approve(owner, get_caller(), new_spender_allowance);
+    // Also rejects a blacklisted spender.
+    _approve(
+        allowances_uref,
+        ledger.blacklist,
+        ledger.package,
+        owner,
+        spender,
+        new_spender_allowance,
+        expires_at,
+    );
 }
 
-fn _approve(owner: Key, spender: Key, amount: U256) {
+fn _approve(
+    allowances_uref: URef,
+    blacklist_uref: URef,
+    package: ContractPackageHash,
+    owner: Key,
+    spender: Key,
+    amount: U256,
+    expires_at: u64,
+) {
     _check_keys_not_null(owner, spender);
-
-    write_allowance(owner, spender, amount);
-
-    events::emit(&ERC20Event::Approval {
-        owner,
-        spender,
-        value: amount,
-    });
+    _check_not_blacklisted(blacklist_uref, &key_to_str(&owner));
+    _check_not_blacklisted(blacklist_uref, &key_to_str(&spender));
+
+    _write_allowance_to(allowances_uref, owner, spender, amount, expires_at);
+
+    events::emit(
+        package,
+        &ERC20Event::Approval {
+            owner,
+            spender,
+            value: amount,
+        },
+    );
 }
 
-fn _mint(to: Key, value: U256) {
-    let total_supply: U256 = get_key::<U256>("total_supply")
+fn _mint(ledger: &Ledger, to: Key, value: U256) {
+    let total_supply: U256 = get_key::<U256>("total_supply");
+    _checkpoint_total_supply(ledger, total_supply);
+    let total_supply = total_supply
         .checked_add(value)
         .ok_or(Error::Overflow)
         .unwrap_or_revert();
 
     set_key("total_supply", total_supply);
 
-    let old_balance = get::<U256>("balances", &key_to_str(&to));
-    let new_to_balance: U256 = old_balance
+    let to_item_key = key_to_str(&to);
+    let to_balance = _read_balance_from(ledger.balances, &to_item_key);
+    let new_to_balance: U256 = to_balance
         .checked_add(value)
         .ok_or(Error::Overflow)
         .unwrap_or_revert();
 
-    set("balances", &key_to_str(&to), new_to_balance);
+    _checkpoint_balance(ledger, &to_item_key, to_balance);
+    _write_balance_to(ledger.balances, &to_item_key, new_to_balance);
 
-    events::emit(&ERC20Event::Transfer {
-        from: Key::Hash([0u8; 32]),
-        to,
+    _move_voting_power(
+        ledger.package,
+        None,
+        _delegate_of(ledger.delegates, &to_item_key),
         value,
-    });
-}
+    );
 
-fn _burn(from: Key, value: U256) {
-    let from_balance = get::<U256>("balances", &key_to_str(&from));
+    events::emit(
+        ledger.package,
+        &ERC20Event::Transfer {
+            from: Key::Hash([0u8; 32]),
+            to,
+            value,
+        },
+    );
+}
 
+fn _burn(ledger: &Ledger, from: Key, value: U256) {
+    let from_item_key = key_to_str(&from);
+    let from_balance = _read_balance_from(ledger.balances, &from_item_key);
     let new_from_balance: U256 = from_balance
         .checked_sub(value)
         .ok_or(Error::InsufficientBalance)
         .unwrap_or_revert();
 
-    set("balances", &key_to_str(&from), new_from_balance);
+    _checkpoint_balance(ledger, &from_item_key, from_balance);
+    _write_balance_to(ledger.balances, &from_item_key, new_from_balance);
 
-    let total_supply: U256 = get_key::<U256>("total_supply")
+    let total_supply: U256 = get_key::<U256>("total_supply");
+    _checkpoint_total_supply(ledger, total_supply);
+    let total_supply = total_supply
         .checked_sub(value)
         .ok_or(Error::Overflow)
         .unwrap_or_revert();
 
     set_key("total_supply", total_supply);
 
-    events::emit(&ERC20Event::Transfer {
-        from,
-        to: Key::Hash([0u8; 32]),
+    _move_voting_power(
+        ledger.package,
+        _delegate_of(ledger.delegates, &from_item_key),
+        None,
         value,
-    });
+    );
+
+    events::emit(
+        ledger.package,
+        &ERC20Event::Transfer {
+            from,
+            to: Key::Hash([0u8; 32]),
+            value,
+        },
+    );
 }
 
 fn _check_keys_not_null(x: Key, y: Key) {
-    if x == Key::Account(AccountHash::default())
-        || x == Key::Hash([0u8; 32])
-        || y == Key::Account(AccountHash::default())
-        || y == Key::Hash([0u8; 32])
-    {
+    if _is_null(x) || _is_null(y) {
         runtime::revert(Error::ZeroAddress);
     }
 }
 
+fn _is_null(key: Key) -> bool {
+    key == Key::Account(AccountHash::default()) || key == Key::Hash([0u8; 32])
+}
+
+/// Reverts unless `name` is a non-empty, bounded name made of ASCII letters, digits, spaces,
+/// `-` and `_`, so it can also be used as a named key and in query paths.
+fn _check_token_name(name: &str) {
+    if name.is_empty() || name.len() > MAX_NAME_LENGTH {
+        runtime::revert(Error::InvalidTokenNameLength);
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == ' ' || c == '-' || c == '_')
+    {
+        runtime::revert(Error::InvalidTokenNameCharacter);
+    }
+}
+
+/// Reverts unless `symbol` is a non-empty, bounded run of ASCII letters and digits.
+fn _check_token_symbol(symbol: &str) {
+    if symbol.is_empty() || symbol.len() > MAX_SYMBOL_LENGTH {
+        runtime::revert(Error::InvalidTokenSymbolLength);
+    }
+    if !symbol.chars().all(|c| c.is_ascii_alphanumeric()) {
+        runtime::revert(Error::InvalidTokenSymbolCharacter);
+    }
+}
+
+/// Reverts if `decimals` exceeds [`MAX_DECIMALS`].
+fn _check_token_decimals(decimals: u8) {
+    if decimals > MAX_DECIMALS {
+        runtime::revert(Error::InvalidTokenDecimals);
+    }
+}
+
+/// Returns the contract package hash behind a `Key::Hash`, reverting for any other key.
+fn _contract_package_hash_of(key: Key) -> ContractPackageHash {
+    key.into_hash()
+        .map(ContractPackageHash::new)
+        .ok_or(Error::NotAContract)
+        .unwrap_or_revert()
+}
+
+/// Returns [`Restriction::UnregisteredReceiver`] if safe transfers are enabled and `recipient` is
+/// a contract that has not registered itself as a token receiver. Accounts are always accepted.
+fn _receiver_restriction(ledger: &Ledger, recipient: Key) -> Option<Restriction> {
+    match (ledger.receivers, recipient) {
+        (Some(receivers), Key::Hash(_))
+            if !get_from::<bool>(receivers, &key_to_str(&recipient)) =>
+        {
+            Some(Restriction::UnregisteredReceiver)
+        }
+        _ => None,
+    }
+}
+
+/// Returns the first restriction on moving `amount` tokens from `sender` to `recipient`, if any.
+/// The safe transfer policy is only applied if `check_receiver` is set.
+///
+/// `_transfer` reverts with the error of this restriction, and `detect_transfer_restriction`
+/// reports its code.
+fn _transfer_restriction(
+    ledger: &Ledger,
+    sender: Key,
+    recipient: Key,
+    amount: U256,
+    check_receiver: bool,
+) -> Option<Restriction> {
+    _route_restriction(ledger, sender, recipient, check_receiver)
+        .or_else(|| _balance_restriction(ledger, sender, amount))
+}
+
+/// Returns the first restriction on moving any tokens from `sender` to `recipient`, if any: the
+/// safe transfer policy if `check_receiver` is set, the null address, then the compliance lists.
+fn _route_restriction(
+    ledger: &Ledger,
+    sender: Key,
+    recipient: Key,
+    check_receiver: bool,
+) -> Option<Restriction> {
+    if check_receiver {
+        if let Some(restriction) = _receiver_restriction(ledger, recipient) {
+            return Some(restriction);
+        }
+    }
+    if _is_null(sender) || _is_null(recipient) {
+        return Some(Restriction::ZeroAddress);
+    }
+    _sender_restriction(ledger, &key_to_str(&sender))
+        .or_else(|| _recipient_restriction(ledger, &key_to_str(&recipient)))
+}
+
+/// Returns [`Restriction::InsufficientBalance`] if `sender` holds fewer than `amount` tokens.
+fn _balance_restriction(ledger: &Ledger, sender: Key, amount: U256) -> Option<Restriction> {
+    if _read_balance_from(ledger.balances, &key_to_str(&sender)) < amount {
+        Some(Restriction::InsufficientBalance)
+    } else {
+        None
+    }
+}
+
+/// Returns the compliance restriction on sending tokens from the address with the balance item
+/// key `item_key`, if any.
+fn _sender_restriction(ledger: &Ledger, item_key: &str) -> Option<Restriction> {
+    if _is_blacklisted(ledger.blacklist, item_key) {
+        Some(Restriction::SenderBlacklisted)
+    } else if _allowlist_blocks(ledger, item_key) {
+        Some(Restriction::SenderNotAllowlisted)
+    } else {
+        None
+    }
+}
+
+/// Returns the compliance restriction on sending tokens to the address with the balance item
+/// key `item_key`, if any.
+fn _recipient_restriction(ledger: &Ledger, item_key: &str) -> Option<Restriction> {
+    if _is_blacklisted(ledger.blacklist, item_key) {
+        Some(Restriction::RecipientBlacklisted)
+    } else if _allowlist_blocks(ledger, item_key) {
+        Some(Restriction::RecipientNotAllowlisted)
+    } else {
+        None
+    }
+}
+
+/// Reverts with the error matching `restriction`, if any.
+fn _check_restriction(restriction: Option<Restriction>) {
+    if let Some(restriction) = restriction {
+        runtime::revert(Error::from(restriction));
+    }
+}
+
+/// Takes the reentrancy lock held by entry points that call out to other contracts.
+///
+/// Balances and allowances are always updated before the call out, so the callee may still use
+/// the plain entry points (e.g. `transfer_from` from `on_approval_received`), but it cannot enter
+/// another entry point that calls out until [`_unlock`] runs.
+fn _lock() {
+    if get_key::<bool>("reentrancy_lock") {
+        runtime::revert(Error::Reentrancy);
+    }
+    set_key("reentrancy_lock", true);
+}
+
+/// Releases the reentrancy lock taken by [`_lock`].
+fn _unlock() {
+    set_key("reentrancy_lock", false);
+}
+
+/// Returns whether the address with the balance item key `item_key` is frozen.
+fn _is_blacklisted(blacklist_uref: URef, item_key: &str) -> bool {
+    get_from(blacklist_uref, item_key)
+}
+
+/// Reverts if the address with the balance item key `item_key` is frozen.
+fn _check_not_blacklisted(blacklist_uref: URef, item_key: &str) {
+    if _is_blacklisted(blacklist_uref, item_key) {
+        runtime::revert(Error::Blacklisted);
+    }
+}
+
+/// Returns whether the address with the balance item key `item_key` is on the allowlist and its
+/// entry has not expired.
+fn _is_allowlisted(allowlist_uref: URef, item_key: &str) -> bool {
+    let expires_at: u64 = get_from(allowlist_uref, item_key);
+    expires_at != NOT_ALLOWLISTED
+        && (expires_at == NO_EXPIRY || u64::from(runtime::get_blocktime()) <= expires_at)
+}
+
+/// Returns whether the allowlist mode is on and the address with the balance item key
+/// `item_key` is not allowlisted.
+fn _allowlist_blocks(ledger: &Ledger, item_key: &str) -> bool {
+    ledger.allowlist.map_or(false, |allowlist_uref| {
+        !_is_allowlisted(allowlist_uref, item_key)
+    })
+}
+
+/// Returns how many tokens can be lent before the total supply or the repayment, the amount
+/// plus its fee, overflows.
+fn _max_flash_loan() -> U256 {
+    let basis_points: u64 = get_key("flash_fee_basis_points");
+    let mintable = U256::MAX - get_key::<U256>("total_supply");
+    let repayable = U256::MAX / (FLASH_FEE_DENOMINATOR + basis_points) * FLASH_FEE_DENOMINATOR;
+    mintable.min(repayable)
+}
+
+/// Returns the flash loan fee owed for lending `amount`, rounded down.
+///
+/// Splits `amount` around the denominator so that no intermediate product overflows.
+fn _flash_fee(amount: U256) -> U256 {
+    let basis_points = U256::from(get_key::<u64>("flash_fee_basis_points"));
+    let denominator = U256::from(FLASH_FEE_DENOMINATOR);
+    amount / denominator * basis_points + amount % denominator * basis_points / denominator
+}
+
+/// Reverts if `memo` is longer than [`MAX_MEMO_LENGTH`].
+fn _check_memo(memo: &str) {
+    if memo.len() > MAX_MEMO_LENGTH {
+        runtime::revert(Error::MemoTooLong);
+    }
+}
+
+/// Reverts unless the immediate caller is the `admin` recorded at install.
+fn _check_caller_is_admin() {
+    let admin: Key = storage::read(_get_uref("admin"))
+        .unwrap_or_revert()
+        .unwrap_or_revert();
+    if get_caller() != admin {
+        runtime::revert(Error::PermissionDenied);
+    }
+}
+
+/// Reverts unless the immediate caller holds the KYC role.
+fn _check_caller_is_kyc_officer() {
+    if !get::<bool>("kyc_officers", &key_to_str(&get_caller())) {
+        runtime::revert(Error::PermissionDenied);
+    }
+}
+
+/// Returns the named argument `name`, or `None` if the caller did not pass it.
+fn get_optional_named_arg<T: FromBytes + CLTyped>(name: &str) -> Option<T> {
+    let mut arg_size: usize = 0;
+    let ret = unsafe {
+        ext_ffi::casper_get_named_arg_size(
+            name.as_bytes().as_ptr(),
+            name.len(),
+            &mut arg_size as *mut usize,
+        )
+    };
+    match api_error::result_from(ret) {
+        Ok(_) => Some(runtime::get_named_arg(name)),
+        Err(ApiError::MissingArgument) => None,
+        Err(error) => runtime::revert(error),
+    }
+}
+
 fn ret<T: CLTyped + ToBytes>(value: T) {
     runtime::ret(CLValue::from_t(value).unwrap_or_revert())
 }
@@ -416,7 +2262,12 @@ fn set_key<T: ToBytes + CLTyped>(name: &str, value: T) {
 }
 
 fn get<T: FromBytes + CLTyped + Default>(dictionary_name: &str, key: &str) -> T {
-    let dictionary_seed_uref = get_dictionary_seed_uref(dictionary_name);
+    get_from(get_dictionary_seed_uref(dictionary_name), key)
+}
+
+/// Reads `key` from the dictionary behind an already resolved seed [`URef`], defaulting if it is
+/// missing.
+fn get_from<T: FromBytes + CLTyped + Default>(dictionary_seed_uref: URef, key: &str) -> T {
     storage::dictionary_get(dictionary_seed_uref, key)
         .unwrap_or_default()
         .unwrap_or_default()
@@ -449,20 +2300,461 @@ fn get_caller() -> Key {
     }
 }
 
+/// Storage read by every transfer, resolved once per entry point and passed down to the
+/// `_transfer` family so that moving tokens does not look the same named keys up again.
+struct Ledger {
+    /// `balances` dictionary.
+    balances: URef,
+    /// Package hash events are emitted under.
+    package: ContractPackageHash,
+    /// `receivers` dictionary, if safe transfers are on.
+    receivers: Option<URef>,
+    /// Id of the latest snapshot and the balance checkpoints, once one was taken.
+    snapshots: Option<(u64, SnapshotCheckpoints)>,
+    /// `delegates` dictionary.
+    delegates: URef,
+    /// `blacklist` dictionary.
+    blacklist: URef,
+    /// `allowlist` dictionary, if the allowlist mode is on.
+    allowlist: Option<URef>,
+}
+
+impl Ledger {
+    fn load() -> Ledger {
+        Ledger {
+            balances: balances_uref(),
+            package: events::contract_package_hash(),
+            receivers: get_key::<bool>("safe_transfers").then(|| _get_uref("receivers")),
+            snapshots: match _current_snapshot_id() {
+                0 => None,
+                id => Some((id, SnapshotCheckpoints::balances())),
+            },
+            delegates: _get_uref("delegates"),
+            blacklist: blacklist_uref(),
+            allowlist: get_key::<bool>("allowlist_required").then(|| _get_uref("allowlist")),
+        }
+    }
+}
+
+/// Returns the `balances` dictionary [`URef`].
+#[inline]
+fn balances_uref() -> URef {
+    _get_uref("balances")
+}
+
+/// Reads the balance stored under an item key produced by [`key_to_str`].
+fn _read_balance_from(balances_uref: URef, item_key: &str) -> U256 {
+    storage::dictionary_get(balances_uref, item_key)
+        .unwrap_or_default()
+        .unwrap_or_default()
+}
+
+/// Writes the balance stored under an item key produced by [`key_to_str`].
+fn _write_balance_to(balances_uref: URef, item_key: &str, amount: U256) {
+    storage::dictionary_put(balances_uref, item_key, amount)
+}
+
 /// Returns the `allowances` dictionary [`URef`].
 #[inline]
 fn allowances_uref() -> URef {
     _get_uref("allowances")
 }
 
+/// Returns the `blacklist` dictionary [`URef`].
+#[inline]
+fn blacklist_uref() -> URef {
+    _get_uref("blacklist")
+}
+
+/// Returns the `metadata` dictionary [`URef`].
+#[inline]
+fn metadata_uref() -> URef {
+    _get_uref("metadata")
+}
+
+/// Reads every entry of the `metadata` dictionary that has been set.
+fn read_metadata() -> BTreeMap<String, String> {
+    let metadata_uref = metadata_uref();
+    let mut metadata = BTreeMap::new();
+    for key in METADATA_KEYS.iter() {
+        let value: Option<String> = storage::dictionary_get(metadata_uref, key).unwrap_or_revert();
+        if let Some(value) = value {
+            metadata.insert(key.to_string(), value);
+        }
+    }
+    metadata
+}
+
 /// Returns the allowance that `owner` has given to `spender`.
 fn read_allowance(owner: Key, spender: Key) -> U256 {
     _read_allowance_from(allowances_uref(), owner, spender)
 }
 
-/// Sets the allowance that `owner` is giving to `spender` to `amount`.
-fn write_allowance(owner: Key, spender: Key, amount: U256) {
-    _write_allowance_to(allowances_uref(), owner, spender, amount)
+/// Returns the recurring allowance that `owner` has given to `spender`, rolled forward to the
+/// period containing the current block time.
+fn read_recurring_allowance(owner: Key, spender: Key) -> RecurringAllowance {
+    let ((amount_per_period, period_ms), (period_start, collected)): RecurringAllowance = get(
+        "recurring_allowances",
+        &make_dictionary_item_key(owner, spender),
+    );
+    let now = u64::from(runtime::get_blocktime());
+    if period_ms == 0 || now < period_start.saturating_add(period_ms) {
+        return ((amount_per_period, period_ms), (period_start, collected));
+    }
+    let elapsed_periods = (now - period_start) / period_ms;
+    let current_period_start = period_start + elapsed_periods * period_ms;
+    (
+        (amount_per_period, period_ms),
+        (current_period_start, U256::zero()),
+    )
+}
+
+/// Sets the recurring allowance that `owner` is giving to `spender`.
+fn write_recurring_allowance(owner: Key, spender: Key, allowance: RecurringAllowance) {
+    set(
+        "recurring_allowances",
+        &make_dictionary_item_key(owner, spender),
+        allowance,
+    );
+}
+
+/// Returns the blake2b hash of `DOMAIN_TAG` and `package`.
+fn _domain_separator(package: ContractPackageHash) -> [u8; 32] {
+    let mut preimage = DOMAIN_TAG.to_vec();
+    preimage.extend_from_slice(&package.value());
+    runtime::blake2b(preimage)
+}
+
+/// Reverts unless `signature` was made by `public_key` over the blake2b hash of the domain
+/// separator, `tag` and `fields`.
+fn _check_signature(
+    package: ContractPackageHash,
+    public_key: &PublicKey,
+    tag: &[u8],
+    fields: &[u8],
+    signature: &[u8],
+) {
+    let mut preimage = _domain_separator(package).to_vec();
+    preimage.extend_from_slice(tag);
+    preimage.extend_from_slice(fields);
+    let message = runtime::blake2b(preimage);
+
+    let signature_bytes: [u8; 64] = signature
+        .try_into()
+        .map_err(|_| Error::InvalidSignature)
+        .unwrap_or_revert();
+    let signature = match public_key {
+        PublicKey::Ed25519(_) => Signature::ed25519(signature_bytes),
+        PublicKey::Secp256k1(_) => Signature::secp256k1(signature_bytes),
+        _ => runtime::revert(Error::InvalidSignature),
+    }
+    .map_err(|_| Error::InvalidSignature)
+    .unwrap_or_revert();
+    crypto::verify(message, &signature, public_key)
+        .map_err(|_| Error::InvalidSignature)
+        .unwrap_or_revert();
+}
+
+/// Returns the current nonce of `owner` and increments it.
+fn _use_nonce(owner: Key) -> u64 {
+    let owner_item_key = key_to_str(&owner);
+    let nonce: u64 = get("nonces", &owner_item_key);
+    set("nonces", &owner_item_key, nonce + 1);
+    nonce
+}
+
+/// Returns the address the holder with the balance item key `item_key` delegates its votes to,
+/// if any.
+fn _delegate_of(delegates_uref: URef, item_key: &str) -> Option<Key> {
+    get_from(delegates_uref, item_key)
+}
+
+/// Points the votes of `delegator` to `delegatee` and moves its current balance along. Does
+/// nothing if `delegatee` already is the delegate.
+fn _delegate(package: ContractPackageHash, delegator: Key, delegatee: Key) {
+    let delegator_item_key = key_to_str(&delegator);
+    let from_delegate: Option<Key> = get("delegates", &delegator_item_key);
+    if from_delegate == Some(delegatee) {
+        return;
+    }
+    set("delegates", &delegator_item_key, Some(delegatee));
+
+    events::emit(
+        package,
+        &ERC20Event::DelegateChanged {
+            delegator,
+            from_delegate,
+            to_delegate: delegatee,
+        },
+    );
+
+    let balance = _read_balance_from(balances_uref(), &delegator_item_key);
+    _move_voting_power(package, from_delegate, Some(delegatee), balance);
+}
+
+/// Moves `amount` votes from the delegate `from` to the delegate `to`. `None` stands for tokens
+/// that are minted, burned or held by an account that has not delegated.
+fn _move_voting_power(
+    package: ContractPackageHash,
+    from: Option<Key>,
+    to: Option<Key>,
+    amount: U256,
+) {
+    if from == to || amount.is_zero() {
+        return;
+    }
+    let checkpoints = VoteCheckpoints::load();
+    if let Some(from) = from {
+        _write_votes(package, checkpoints, from, |votes| {
+            votes.checked_sub(amount)
+        });
+    }
+    if let Some(to) = to {
+        _write_votes(package, checkpoints, to, |votes| votes.checked_add(amount));
+    }
+}
+
+/// Checkpoints the votes of `delegate` as updated by `update` at the current block time.
+fn _write_votes(
+    package: ContractPackageHash,
+    checkpoints: VoteCheckpoints,
+    delegate: Key,
+    update: impl Fn(U256) -> Option<U256>,
+) {
+    let count = _num_vote_checkpoints(checkpoints, delegate);
+    let (last_time, previous_balance) = match count {
+        0 => (0, U256::zero()),
+        _ => _vote_checkpoint(checkpoints, delegate, count - 1),
+    };
+    let new_balance = update(previous_balance)
+        .ok_or(Error::Overflow)
+        .unwrap_or_revert();
+
+    // Several changes within the same block only keep the last one.
+    let now = u64::from(runtime::get_blocktime());
+    let index = match count {
+        0 => 0,
+        _ if last_time == now => count - 1,
+        _ => count,
+    };
+    storage::dictionary_put(
+        checkpoints.entries,
+        &_vote_checkpoint_item_key(delegate, index),
+        (now, new_balance),
+    );
+    if index == count {
+        storage::dictionary_put(checkpoints.counts, &key_to_str(&delegate), count + 1);
+    }
+
+    events::emit(
+        package,
+        &ERC20Event::DelegateVotesChanged {
+            delegate,
+            previous_balance,
+            new_balance,
+        },
+    );
+}
+
+/// The dictionaries holding the vote checkpoints, resolved once by the callers that read or
+/// write several of them.
+#[derive(Clone, Copy)]
+struct VoteCheckpoints {
+    /// `vote_checkpoints` dictionary, keyed by [`_vote_checkpoint_item_key`].
+    entries: URef,
+    /// `num_vote_checkpoints` dictionary, keyed by [`key_to_str`] of the delegate.
+    counts: URef,
+}
+
+impl VoteCheckpoints {
+    fn load() -> VoteCheckpoints {
+        VoteCheckpoints {
+            entries: _get_uref("vote_checkpoints"),
+            counts: _get_uref("num_vote_checkpoints"),
+        }
+    }
+}
+
+/// Returns how many vote checkpoints `delegate` has.
+fn _num_vote_checkpoints(checkpoints: VoteCheckpoints, delegate: Key) -> u32 {
+    get_from(checkpoints.counts, &key_to_str(&delegate))
+}
+
+/// Returns the `index`th vote checkpoint of `delegate`, counting from the oldest.
+fn _vote_checkpoint(checkpoints: VoteCheckpoints, delegate: Key, index: u32) -> VoteCheckpoint {
+    get_from(
+        checkpoints.entries,
+        &_vote_checkpoint_item_key(delegate, index),
+    )
+}
+
+/// Returns the current votes of `delegate`, from its latest checkpoint.
+fn _current_votes(checkpoints: VoteCheckpoints, delegate: Key) -> U256 {
+    match _num_vote_checkpoints(checkpoints, delegate) {
+        0 => U256::zero(),
+        count => _vote_checkpoint(checkpoints, delegate, count - 1).1,
+    }
+}
+
+/// Creates the dictionary item key of the `index`th vote checkpoint of `delegate`.
+fn _vote_checkpoint_item_key(delegate: Key, index: u32) -> String {
+    let mut preimage = delegate.to_bytes().unwrap_or_revert();
+    preimage.append(&mut index.to_bytes().unwrap_or_revert());
+    hex::encode(&runtime::blake2b(&preimage))
+}
+
+/// Returns the id of the latest snapshot, or zero if none was taken yet.
+fn _current_snapshot_id() -> u64 {
+    get_key("snapshot_id")
+}
+
+/// Reverts unless a snapshot with the given `id` was taken.
+fn _check_snapshot_id(id: u64) {
+    if id == 0 || id > _current_snapshot_id() {
+        runtime::revert(Error::InvalidSnapshotId);
+    }
+}
+
+/// The dictionaries holding the snapshot checkpoints of one kind of value, keyed by series: the
+/// balance item key of each account, or [`TOTAL_SUPPLY_SERIES`].
+#[derive(Clone, Copy)]
+struct SnapshotCheckpoints {
+    /// Checkpoints, keyed by [`_snapshot_checkpoint_item_key`].
+    entries: URef,
+    /// Number of checkpoints of each series and the snapshot id of its latest, as a
+    /// [`SnapshotHead`].
+    heads: URef,
+}
+
+impl SnapshotCheckpoints {
+    fn balances() -> SnapshotCheckpoints {
+        SnapshotCheckpoints {
+            entries: _get_uref("balance_snapshots"),
+            heads: _get_uref("balance_snapshot_heads"),
+        }
+    }
+
+    fn total_supply() -> SnapshotCheckpoints {
+        SnapshotCheckpoints {
+            entries: _get_uref("total_supply_snapshots"),
+            heads: _get_uref("total_supply_snapshot_heads"),
+        }
+    }
+}
+
+/// Creates the dictionary item key of the `index`th snapshot checkpoint of `series`.
+fn _snapshot_checkpoint_item_key(series: &str, index: u32) -> String {
+    let mut preimage = series.as_bytes().to_vec();
+    preimage.append(&mut index.to_bytes().unwrap_or_revert());
+    hex::encode(&runtime::blake2b(&preimage))
+}
+
+/// Returns the `index`th snapshot checkpoint of `series`, counting from the oldest.
+fn _snapshot_checkpoint(
+    checkpoints: SnapshotCheckpoints,
+    series: &str,
+    index: u32,
+) -> SnapshotCheckpoint {
+    get_from(
+        checkpoints.entries,
+        &_snapshot_checkpoint_item_key(series, index),
+    )
+}
+
+/// Records `value` of `series` for snapshot `snapshot_id` before it changes, unless the series
+/// already has a checkpoint for it.
+fn _checkpoint(checkpoints: SnapshotCheckpoints, series: &str, snapshot_id: u64, value: U256) {
+    let (count, last_id): SnapshotHead = get_from(checkpoints.heads, series);
+    if last_id >= snapshot_id {
+        return;
+    }
+    storage::dictionary_put(
+        checkpoints.entries,
+        &_snapshot_checkpoint_item_key(series, count),
+        (snapshot_id, value),
+    );
+    storage::dictionary_put(checkpoints.heads, series, (count + 1, snapshot_id));
+}
+
+/// Returns the value of `series` recorded for snapshot `id`, or `None` if it has not changed
+/// since then. Each checkpoint holds the value from before the first change made after its
+/// snapshot.
+fn _value_at(checkpoints: SnapshotCheckpoints, series: &str, id: u64) -> Option<U256> {
+    let (count, _): SnapshotHead = get_from(checkpoints.heads, series);
+
+    // First checkpoint of snapshot `id` or a later one, found by binary search.
+    let (mut low, mut high) = (0, count);
+    while low < high {
+        let middle = low + (high - low) / 2;
+        let (checkpoint_id, _) = _snapshot_checkpoint(checkpoints, series, middle);
+        if checkpoint_id < id {
+            low = middle + 1;
+        } else {
+            high = middle;
+        }
+    }
+    (low < count).then(|| _snapshot_checkpoint(checkpoints, series, low).1)
+}
+
+/// Records `balance` of `item_key` for the latest snapshot before it changes. Accounts only get
+/// a checkpoint on their first change after a snapshot, and nothing is written before the first.
+fn _checkpoint_balance(ledger: &Ledger, item_key: &str, balance: U256) {
+    if let Some((snapshot_id, checkpoints)) = ledger.snapshots {
+        _checkpoint(checkpoints, item_key, snapshot_id, balance);
+    }
+}
+
+/// Records `total_supply` for the latest snapshot before it changes.
+fn _checkpoint_total_supply(ledger: &Ledger, total_supply: U256) {
+    if let Some((snapshot_id, _)) = ledger.snapshots {
+        _checkpoint(
+            SnapshotCheckpoints::total_supply(),
+            TOTAL_SUPPLY_SERIES,
+            snapshot_id,
+            total_supply,
+        );
+    }
+}
+
+/// Returns the `authorization_states` item key of `nonce` of `authorizer`.
+fn _authorization_item_key(authorizer: Key, nonce: [u8; 32]) -> String {
+    let mut preimage = authorizer.to_bytes().unwrap_or_revert();
+    preimage.extend_from_slice(&nonce);
+    hex::encode(runtime::blake2b(preimage))
+}
+
+/// Marks `nonce` of `authorizer` as used and emits `event`, reverting if it already was.
+fn _use_authorization(
+    package: ContractPackageHash,
+    authorizer: Key,
+    nonce: [u8; 32],
+    event: ERC20Event,
+) {
+    let item_key = _authorization_item_key(authorizer, nonce);
+    if get::<bool>("authorization_states", &item_key) {
+        runtime::revert(Error::AuthorizationUsed);
+    }
+    set("authorization_states", &item_key, true);
+    events::emit(package, &event);
+}
+
+/// Adds `spender` to the spenders of `owner` when it is given a non-zero allowance.
+fn _index_spender(owner: Key, spender: Key, amount: U256) {
+    if amount.is_zero() {
+        return;
+    }
+    let owner_item_key = key_to_str(&owner);
+    let mut spenders: Vec<Key> = get("spenders", &owner_item_key);
+    if !spenders.contains(&spender) {
+        spenders.push(spender);
+        set("spenders", &owner_item_key, spenders);
+    }
+}
+
+/// Returns whether `operator` may move all of the tokens of `holder`. Every holder is its own
+/// operator.
+fn _is_operator_for(operator: Key, holder: Key) -> bool {
+    operator == holder || get::<bool>("operators", &make_dictionary_item_key(holder, operator))
 }
 
 /// Creates a dictionary item key for an (owner, spender) pair.
@@ -475,18 +2767,35 @@ fn make_dictionary_item_key(owner: Key, spender: Key) -> String {
     hex::encode(&key_bytes)
 }
 
-/// Writes an allowance for owner and spender for a specific amount.
-fn _write_allowance_to(allowances_uref: URef, owner: Key, spender: Key, amount: U256) {
+/// Writes an allowance for owner and spender for a specific amount, valid until `expires_at`.
+fn _write_allowance_to(
+    allowances_uref: URef,
+    owner: Key,
+    spender: Key,
+    amount: U256,
+    expires_at: u64,
+) {
     let dictionary_item_key = make_dictionary_item_key(owner, spender);
-    storage::dictionary_put(allowances_uref, &dictionary_item_key, amount)
+    storage::dictionary_put(allowances_uref, &dictionary_item_key, (amount, expires_at))
 }
 
 /// Reads an allowance for an owner and spender.
 fn _read_allowance_from(allowances_uref: URef, owner: Key, spender: Key) -> U256 {
+    _read_allowance_entry_from(allowances_uref, owner, spender).0
+}
+
+/// Reads an allowance for an owner and spender along with its deadline. Once the block time
+/// passes the deadline the allowance reads as zero.
+fn _read_allowance_entry_from(allowances_uref: URef, owner: Key, spender: Key) -> (U256, u64) {
     let dictionary_item_key = make_dictionary_item_key(owner, spender);
-    storage::dictionary_get(allowances_uref, &dictionary_item_key)
-        .unwrap_or_revert()
-        .unwrap_or_default()
+    let (amount, expires_at): (U256, u64) =
+        storage::dictionary_get(allowances_uref, &dictionary_item_key)
+            .unwrap_or_revert()
+            .unwrap_or((U256::zero(), NO_EXPIRY));
+    if expires_at != NO_EXPIRY && u64::from(runtime::get_blocktime()) > expires_at {
+        return (U256::zero(), expires_at);
+    }
+    (amount, expires_at)
 }
 
 /// Gets [`URef`] under a name.
@@ -0,0 +1,75 @@
+//! Transfer restrictions reported by `detect_transfer_restriction` (ERC-1404).
+use crate::error::Error;
+
+/// Code returned by `detect_transfer_restriction` when the transfer would go through.
+pub const NO_RESTRICTION: u8 = 0;
+
+/// Reasons for which `transfer` rejects a transfer.
+///
+/// `_transfer` and `detect_transfer_restriction` both go through `_transfer_restriction`, which
+/// returns these in the order the checks run. Each maps to the error the transfer reverts with.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Restriction {
+    /// Safe transfers are on and the recipient is an unregistered contract.
+    UnregisteredReceiver = 1,
+    /// Sender or recipient is the null address.
+    ZeroAddress = 2,
+    /// Sender is frozen.
+    SenderBlacklisted = 3,
+    /// Allowlist mode is on and the sender is not allowlisted.
+    SenderNotAllowlisted = 4,
+    /// Recipient is frozen.
+    RecipientBlacklisted = 5,
+    /// Allowlist mode is on and the recipient is not allowlisted.
+    RecipientNotAllowlisted = 6,
+    /// Sender does not have enough balance.
+    InsufficientBalance = 7,
+}
+
+impl Restriction {
+    pub fn code(self) -> u8 {
+        self as u8
+    }
+
+    pub fn from_code(code: u8) -> Option<Restriction> {
+        match code {
+            1 => Some(Restriction::UnregisteredReceiver),
+            2 => Some(Restriction::ZeroAddress),
+            3 => Some(Restriction::SenderBlacklisted),
+            4 => Some(Restriction::SenderNotAllowlisted),
+            5 => Some(Restriction::RecipientBlacklisted),
+            6 => Some(Restriction::RecipientNotAllowlisted),
+            7 => Some(Restriction::InsufficientBalance),
+            _ => None,
+        }
+    }
+
+    pub fn message(self) -> &'static str {
+        match self {
+            Restriction::UnregisteredReceiver => "Recipient contract is not a registered receiver",
+            Restriction::ZeroAddress => "Sender or recipient is the null address",
+            Restriction::SenderBlacklisted => "Sender is blacklisted",
+            Restriction::SenderNotAllowlisted => "Sender is not allowlisted",
+            Restriction::RecipientBlacklisted => "Recipient is blacklisted",
+            Restriction::RecipientNotAllowlisted => "Recipient is not allowlisted",
+            Restriction::InsufficientBalance => "Sender does not have enough balance",
+        }
+    }
+}
+
+impl From<Restriction> for Error {
+    fn from(restriction: Restriction) -> Self {
+        match restriction {
+            Restriction::UnregisteredReceiver => Error::UnregisteredReceiver,
+            Restriction::ZeroAddress => Error::ZeroAddress,
+            Restriction::SenderBlacklisted | Restriction::RecipientBlacklisted => {
+                Error::Blacklisted
+            }
+            Restriction::SenderNotAllowlisted | Restriction::RecipientNotAllowlisted => {
+                Error::NotAllowlisted
+            }
+            Restriction::InsufficientBalance => Error::InsufficientBalance,
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Contains definition of the governor contract entry points.
+use alloc::{string::String, vec, vec::Vec};
+
+use types::{
+    bytesrepr::Bytes, CLType, CLTyped, EntryPoint, EntryPointAccess, EntryPointType, EntryPoints,
+    Parameter, U256,
+};
+
+pub fn endpoint(name: &str, param: Vec<Parameter>, ret: CLType) -> EntryPoint {
+    EntryPoint::new(
+        String::from(name),
+        param,
+        ret,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+/// Returns the `propose` entry point.
+pub fn propose() -> EntryPoint {
+    endpoint(
+        "propose",
+        vec![
+            Parameter::new("target", CLType::Key),
+            Parameter::new("entry_point", CLType::String),
+            Parameter::new("args", Bytes::cl_type()),
+            Parameter::new("description", CLType::String),
+        ],
+        CLType::U64,
+    )
+}
+
+/// Returns the `cast_vote` entry point.
+pub fn cast_vote() -> EntryPoint {
+    endpoint(
+        "cast_vote",
+        vec![
+            Parameter::new("proposal_id", CLType::U64),
+            Parameter::new("support", CLType::U8),
+        ],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `queue` entry point.
+pub fn queue() -> EntryPoint {
+    endpoint(
+        "queue",
+        vec![Parameter::new("proposal_id", CLType::U64)],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `execute` entry point.
+pub fn execute() -> EntryPoint {
+    endpoint(
+        "execute",
+        vec![Parameter::new("proposal_id", CLType::U64)],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `cancel` entry point.
+pub fn cancel() -> EntryPoint {
+    endpoint(
+        "cancel",
+        vec![Parameter::new("proposal_id", CLType::U64)],
+        CLType::Unit,
+    )
+}
+
+/// Returns the `state` entry point.
+pub fn state() -> EntryPoint {
+    endpoint(
+        "state",
+        vec![Parameter::new("proposal_id", CLType::U64)],
+        CLType::U8,
+    )
+}
+
+/// Returns the `proposal_votes` entry point.
+pub fn proposal_votes() -> EntryPoint {
+    endpoint(
+        "proposal_votes",
+        vec![Parameter::new("proposal_id", CLType::U64)],
+        <(U256, U256, U256)>::cl_type(),
+    )
+}
+
+/// Returns the `has_voted` entry point.
+pub fn has_voted() -> EntryPoint {
+    endpoint(
+        "has_voted",
+        vec![
+            Parameter::new("proposal_id", CLType::U64),
+            Parameter::new("account", CLType::Key),
+        ],
+        CLType::Bool,
+    )
+}
+
+/// Returns the default set of governor contract entry points.
+pub fn default() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(propose());
+    entry_points.add_entry_point(cast_vote());
+    entry_points.add_entry_point(queue());
+    entry_points.add_entry_point(execute());
+    entry_points.add_entry_point(cancel());
+    entry_points.add_entry_point(state());
+    entry_points.add_entry_point(proposal_votes());
+    entry_points.add_entry_point(has_voted());
+    entry_points
+}
@@ -0,0 +1,73 @@
+//! Error handling on the casper platform.
+use types::ApiError;
+
+/// Errors which can be returned by the governor.
+///
+/// When an `Error` is returned from a smart contract, it is converted to an [`ApiError::User`].
+///
+/// The codes start at `u16::MAX - 64` (i.e. 65471), below the codes of the token, so that a
+/// revert inside a call to the token can be told apart from a revert of the governor itself.
+pub enum Error {
+    /// Governor called from within an invalid context.
+    InvalidContext,
+    /// Proposer does not have enough votes to create a proposal.
+    BelowProposalThreshold,
+    /// Proposal with the given id does not exist.
+    UnknownProposal,
+    /// Arguments of a proposal are not serialized `RuntimeArgs`, or its target is not a contract.
+    InvalidProposalArgs,
+    /// Proposal is not open for voting.
+    ProposalNotActive,
+    /// Voter already cast a vote on the proposal.
+    AlreadyVoted,
+    /// Vote is neither against (0), for (1) nor abstain (2).
+    InvalidSupport,
+    /// Proposal did not pass and cannot be queued.
+    ProposalNotSucceeded,
+    /// Proposal is not queued and cannot be executed.
+    ProposalNotQueued,
+    /// Timelock of a queued proposal has not elapsed yet.
+    TimelockNotElapsed,
+    /// Caller is not allowed to perform the action.
+    PermissionDenied,
+    /// Proposal was already executed or canceled.
+    ProposalNotCancelable,
+    /// Voting period of the governor is zero.
+    InvalidVotingPeriod,
+}
+
+// u16::MAX = 65535
+const ERROR_INVALID_CONTEXT: u16 = u16::MAX - 64; // 65471
+const ERROR_BELOW_PROPOSAL_THRESHOLD: u16 = u16::MAX - 65; // 65470
+const ERROR_UNKNOWN_PROPOSAL: u16 = u16::MAX - 66; // 65469
+const ERROR_INVALID_PROPOSAL_ARGS: u16 = u16::MAX - 67; // 65468
+const ERROR_PROPOSAL_NOT_ACTIVE: u16 = u16::MAX - 68; // 65467
+const ERROR_ALREADY_VOTED: u16 = u16::MAX - 69; // 65466
+const ERROR_INVALID_SUPPORT: u16 = u16::MAX - 70; // 65465
+const ERROR_PROPOSAL_NOT_SUCCEEDED: u16 = u16::MAX - 71; // 65464
+const ERROR_PROPOSAL_NOT_QUEUED: u16 = u16::MAX - 72; // 65463
+const ERROR_TIMELOCK_NOT_ELAPSED: u16 = u16::MAX - 73; // 65462
+const ERROR_PERMISSION_DENIED: u16 = u16::MAX - 74; // 65461
+const ERROR_PROPOSAL_NOT_CANCELABLE: u16 = u16::MAX - 75; // 65460
+const ERROR_INVALID_VOTING_PERIOD: u16 = u16::MAX - 76; // 65459
+
+impl From<Error> for ApiError {
+    fn from(error: Error) -> Self {
+        let user_error = match error {
+            Error::InvalidContext => ERROR_INVALID_CONTEXT,
+            Error::BelowProposalThreshold => ERROR_BELOW_PROPOSAL_THRESHOLD,
+            Error::UnknownProposal => ERROR_UNKNOWN_PROPOSAL,
+            Error::InvalidProposalArgs => ERROR_INVALID_PROPOSAL_ARGS,
+            Error::ProposalNotActive => ERROR_PROPOSAL_NOT_ACTIVE,
+            Error::AlreadyVoted => ERROR_ALREADY_VOTED,
+            Error::InvalidSupport => ERROR_INVALID_SUPPORT,
+            Error::ProposalNotSucceeded => ERROR_PROPOSAL_NOT_SUCCEEDED,
+            Error::ProposalNotQueued => ERROR_PROPOSAL_NOT_QUEUED,
+            Error::TimelockNotElapsed => ERROR_TIMELOCK_NOT_ELAPSED,
+            Error::PermissionDenied => ERROR_PERMISSION_DENIED,
+            Error::ProposalNotCancelable => ERROR_PROPOSAL_NOT_CANCELABLE,
+            Error::InvalidVotingPeriod => ERROR_INVALID_VOTING_PERIOD,
+        };
+        ApiError::User(user_error)
+    }
+}
@@ -0,0 +1,100 @@
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+};
+
+use contract::contract_api::storage;
+use types::{ContractPackageHash, Key, URef, U256};
+
+use crate::get_key;
+
+pub enum GovernorEvent {
+    ProposalCreated {
+        proposal_id: u64,
+        proposer: Key,
+        target: Key,
+        entry_point: String,
+        description: String,
+        vote_start: u64,
+        vote_end: u64,
+    },
+    VoteCast {
+        proposal_id: u64,
+        voter: Key,
+        support: u8,
+        weight: U256,
+    },
+    ProposalQueued {
+        proposal_id: u64,
+        eta: u64,
+    },
+    ProposalExecuted {
+        proposal_id: u64,
+    },
+    ProposalCanceled {
+        proposal_id: u64,
+    },
+}
+
+impl GovernorEvent {
+    pub fn type_name(&self) -> String {
+        match self {
+            GovernorEvent::ProposalCreated { .. } => "proposal_created",
+            GovernorEvent::VoteCast { .. } => "vote_cast",
+            GovernorEvent::ProposalQueued { .. } => "proposal_queued",
+            GovernorEvent::ProposalExecuted { .. } => "proposal_executed",
+            GovernorEvent::ProposalCanceled { .. } => "proposal_canceled",
+        }
+        .to_string()
+    }
+}
+
+pub fn contract_package_hash() -> ContractPackageHash {
+    get_key::<ContractPackageHash>("contract_package_hash")
+}
+
+/// Records `governor_event` as a new `URef`, tagged with the governor's package hash.
+pub(crate) fn emit(governor_event: &GovernorEvent) {
+    let mut event = BTreeMap::new();
+    event.insert("contract_package_hash", contract_package_hash().to_string());
+    event.insert("event_type", governor_event.type_name());
+    match governor_event {
+        GovernorEvent::ProposalCreated {
+            proposal_id,
+            proposer,
+            target,
+            entry_point,
+            description,
+            vote_start,
+            vote_end,
+        } => {
+            event.insert("proposal_id", proposal_id.to_string());
+            event.insert("proposer", proposer.to_string());
+            event.insert("target", target.to_string());
+            event.insert("entry_point", entry_point.clone());
+            event.insert("description", description.clone());
+            event.insert("vote_start", vote_start.to_string());
+            event.insert("vote_end", vote_end.to_string());
+        }
+        GovernorEvent::VoteCast {
+            proposal_id,
+            voter,
+            support,
+            weight,
+        } => {
+            event.insert("proposal_id", proposal_id.to_string());
+            event.insert("voter", voter.to_string());
+            event.insert("support", support.to_string());
+            event.insert("weight", weight.to_string());
+        }
+        GovernorEvent::ProposalQueued { proposal_id, eta } => {
+            event.insert("proposal_id", proposal_id.to_string());
+            event.insert("eta", eta.to_string());
+        }
+        GovernorEvent::ProposalExecuted { proposal_id }
+        | GovernorEvent::ProposalCanceled { proposal_id } => {
+            event.insert("proposal_id", proposal_id.to_string());
+        }
+    };
+    let _: URef = storage::new_uref(event);
+}
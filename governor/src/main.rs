@@ -0,0 +1,473 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::string::{String, ToString};
+use core::convert::TryInto;
+
+use contract::{
+    contract_api::{
+        runtime,
+        storage::{self, create_contract_package_at_hash},
+    },
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use types::{
+    bytesrepr::{Bytes, FromBytes, ToBytes},
+    contracts::NamedKeys,
+    runtime_args,
+    system::CallStackElement,
+    CLTyped, CLValue, ContractPackageHash, Key, RuntimeArgs, URef, U256,
+};
+pub mod entry_points;
+pub mod error;
+pub mod events;
+use error::Error;
+use events::GovernorEvent;
+
+/// A proposal, stored as `(proposer, (vote_start, vote_end), (target, entry_point, args))`.
+type Proposal = (Key, (u64, u64), (Key, String, Bytes));
+/// Votes cast on a proposal, stored as `(against, for, abstain)`.
+type ProposalVotes = (U256, U256, U256);
+/// Progress of a proposal after the vote, stored as `(eta, executed, canceled)` where `eta` is
+/// zero until the proposal is queued.
+type ProposalExecution = (u64, bool, bool);
+
+/// States of a proposal, as returned by `state`.
+#[derive(Clone, Copy, PartialEq)]
+enum ProposalState {
+    Pending = 0,
+    Active = 1,
+    Canceled = 2,
+    Defeated = 3,
+    Succeeded = 4,
+    Queued = 5,
+    Executed = 6,
+}
+
+/// # Purpose
+/// * Creates a proposal to call an entry point of a contract once the vote passed. The caller
+///   must have had at least `proposal_threshold` votes at the previous block time.
+/// # Arguments
+/// * `target` - `Key` -> Contract package hash of the contract to call.
+/// * `entry_point` - `String` -> Name of the entry point to call. It must return `()`.
+/// * `args` - `Bytes` -> Serialized `RuntimeArgs` of the call.
+/// * `description` - `String` -> Description of the proposal, only recorded in the event.
+/// # Returns
+/// * `proposal_id` - `u64` -> Id of the new proposal, starting at 1.
+#[no_mangle]
+pub extern "C" fn propose() {
+    let target: Key = runtime::get_named_arg("target");
+    let entry_point: String = runtime::get_named_arg("entry_point");
+    let args: Bytes = runtime::get_named_arg("args");
+    let description: String = runtime::get_named_arg("description");
+
+    _target_package(target);
+    _call_args(&args);
+
+    let proposer = get_caller();
+    let now = u64::from(runtime::get_blocktime());
+    if _past_votes(proposer, now.saturating_sub(1)) < get_key::<U256>("proposal_threshold") {
+        runtime::revert(Error::BelowProposalThreshold);
+    }
+
+    let proposal_id = get_key::<u64>("proposal_count") + 1;
+    set_key("proposal_count", proposal_id);
+
+    let vote_start = now + get_key::<u64>("voting_delay");
+    let vote_end = vote_start + get_key::<u64>("voting_period");
+    set(
+        "proposals",
+        &proposal_id.to_string(),
+        (
+            proposer,
+            (vote_start, vote_end),
+            (target, entry_point.clone(), args),
+        ),
+    );
+
+    events::emit(&GovernorEvent::ProposalCreated {
+        proposal_id,
+        proposer,
+        target,
+        entry_point,
+        description,
+        vote_start,
+        vote_end,
+    });
+    ret(proposal_id)
+}
+
+/// # Purpose
+/// * Casts the caller's vote, weighted by its votes on the token when voting started.
+/// # Arguments
+/// * `proposal_id` - `u64` -> Id of the proposal.
+/// * `support` - `u8` -> 0 to vote against, 1 to vote for, 2 to abstain.
+#[no_mangle]
+pub extern "C" fn cast_vote() {
+    let proposal_id: u64 = runtime::get_named_arg("proposal_id");
+    let support: u8 = runtime::get_named_arg("support");
+
+    if _state(proposal_id) != ProposalState::Active {
+        runtime::revert(Error::ProposalNotActive);
+    }
+
+    let voter = get_caller();
+    let receipt_item_key = _receipt_item_key(proposal_id, voter);
+    if get::<bool>("receipts", &receipt_item_key) {
+        runtime::revert(Error::AlreadyVoted);
+    }
+    set("receipts", &receipt_item_key, true);
+
+    let (_, (vote_start, _), _) = _proposal(proposal_id);
+    let weight = _past_votes(voter, vote_start);
+
+    let proposal_item_key = proposal_id.to_string();
+    let (against, for_votes, abstain): ProposalVotes = get("proposal_votes", &proposal_item_key);
+    let votes = match support {
+        0 => (
+            against.checked_add(weight).unwrap_or_revert(),
+            for_votes,
+            abstain,
+        ),
+        1 => (
+            against,
+            for_votes.checked_add(weight).unwrap_or_revert(),
+            abstain,
+        ),
+        2 => (
+            against,
+            for_votes,
+            abstain.checked_add(weight).unwrap_or_revert(),
+        ),
+        _ => runtime::revert(Error::InvalidSupport),
+    };
+    set("proposal_votes", &proposal_item_key, votes);
+
+    events::emit(&GovernorEvent::VoteCast {
+        proposal_id,
+        voter,
+        support,
+        weight,
+    });
+}
+
+/// # Purpose
+/// * Queues a proposal that passed, starting its timelock.
+/// # Arguments
+/// * `proposal_id` - `u64` -> Id of the proposal.
+#[no_mangle]
+pub extern "C" fn queue() {
+    let proposal_id: u64 = runtime::get_named_arg("proposal_id");
+
+    if _state(proposal_id) != ProposalState::Succeeded {
+        runtime::revert(Error::ProposalNotSucceeded);
+    }
+
+    let eta = u64::from(runtime::get_blocktime()) + get_key::<u64>("timelock_delay");
+    set(
+        "proposal_executions",
+        &proposal_id.to_string(),
+        (eta, false, false),
+    );
+
+    events::emit(&GovernorEvent::ProposalQueued { proposal_id, eta });
+}
+
+/// # Purpose
+/// * Executes a queued proposal once its timelock elapsed, calling its entry point as the
+///   governor.
+/// # Arguments
+/// * `proposal_id` - `u64` -> Id of the proposal.
+#[no_mangle]
+pub extern "C" fn execute() {
+    let proposal_id: u64 = runtime::get_named_arg("proposal_id");
+
+    if _state(proposal_id) != ProposalState::Queued {
+        runtime::revert(Error::ProposalNotQueued);
+    }
+
+    let proposal_item_key = proposal_id.to_string();
+    let (eta, _, _): ProposalExecution = get("proposal_executions", &proposal_item_key);
+    if u64::from(runtime::get_blocktime()) < eta {
+        runtime::revert(Error::TimelockNotElapsed);
+    }
+    set(
+        "proposal_executions",
+        &proposal_item_key,
+        (eta, true, false),
+    );
+
+    let (_, _, (target, entry_point, args)) = _proposal(proposal_id);
+    runtime::call_versioned_contract::<()>(
+        _target_package(target),
+        None,
+        &entry_point,
+        _call_args(&args),
+    );
+
+    events::emit(&GovernorEvent::ProposalExecuted { proposal_id });
+}
+
+/// # Purpose
+/// * Cancels a proposal that has not been executed. Only its proposer may call it.
+/// # Arguments
+/// * `proposal_id` - `u64` -> Id of the proposal.
+#[no_mangle]
+pub extern "C" fn cancel() {
+    let proposal_id: u64 = runtime::get_named_arg("proposal_id");
+
+    let (proposer, _, _) = _proposal(proposal_id);
+    if get_caller() != proposer {
+        runtime::revert(Error::PermissionDenied);
+    }
+    let state = _state(proposal_id);
+    if state == ProposalState::Executed || state == ProposalState::Canceled {
+        runtime::revert(Error::ProposalNotCancelable);
+    }
+
+    let proposal_item_key = proposal_id.to_string();
+    let (eta, _, _): ProposalExecution = get("proposal_executions", &proposal_item_key);
+    set(
+        "proposal_executions",
+        &proposal_item_key,
+        (eta, false, true),
+    );
+
+    events::emit(&GovernorEvent::ProposalCanceled { proposal_id });
+}
+
+/// # Purpose
+/// * Returns the state of a proposal.
+/// # Arguments
+/// * `proposal_id` - `u64` -> Id of the proposal.
+/// # Returns
+/// * `state` - `u8` -> 0 pending, 1 active, 2 canceled, 3 defeated, 4 succeeded, 5 queued or
+///   6 executed.
+#[no_mangle]
+pub extern "C" fn state() {
+    let proposal_id: u64 = runtime::get_named_arg("proposal_id");
+    ret(_state(proposal_id) as u8)
+}
+
+/// # Purpose
+/// * Returns the votes cast on a proposal.
+/// # Arguments
+/// * `proposal_id` - `u64` -> Id of the proposal.
+/// # Returns
+/// * `votes` - `(U256, U256, U256)` -> Votes against, for and abstaining.
+#[no_mangle]
+pub extern "C" fn proposal_votes() {
+    let proposal_id: u64 = runtime::get_named_arg("proposal_id");
+    let votes: ProposalVotes = get("proposal_votes", &proposal_id.to_string());
+    ret(votes)
+}
+
+/// # Purpose
+/// * Returns whether an account voted on a proposal.
+/// # Arguments
+/// * `proposal_id` - `u64` -> Id of the proposal.
+/// * `account` - `Key` -> Address of the voter.
+/// # Returns
+/// * `voted` - `bool` -> Whether the account cast a vote.
+#[no_mangle]
+pub extern "C" fn has_voted() {
+    let proposal_id: u64 = runtime::get_named_arg("proposal_id");
+    let account: Key = runtime::get_named_arg("account");
+    let voted: bool = get("receipts", &_receipt_item_key(proposal_id, account));
+    ret(voted)
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let token: Key = runtime::get_named_arg("token");
+    let proposal_threshold: U256 = runtime::get_named_arg("proposal_threshold");
+    let quorum: U256 = runtime::get_named_arg("quorum");
+    let voting_delay: u64 = runtime::get_named_arg("voting_delay");
+    let voting_period: u64 = runtime::get_named_arg("voting_period");
+    let timelock_delay: u64 = runtime::get_named_arg("timelock_delay");
+
+    if voting_period == 0 {
+        runtime::revert(Error::InvalidVotingPeriod);
+    }
+
+    let (contract_package_hash, access_uref) = create_contract_package_at_hash();
+
+    let mut named_keys = NamedKeys::new();
+    named_keys.insert(
+        "token".to_string(),
+        storage::new_uref(_target_package(token)).into(),
+    );
+    named_keys.insert(
+        "proposal_threshold".to_string(),
+        storage::new_uref(proposal_threshold).into(),
+    );
+    named_keys.insert("quorum".to_string(), storage::new_uref(quorum).into());
+    named_keys.insert(
+        "voting_delay".to_string(),
+        storage::new_uref(voting_delay).into(),
+    );
+    named_keys.insert(
+        "voting_period".to_string(),
+        storage::new_uref(voting_period).into(),
+    );
+    named_keys.insert(
+        "timelock_delay".to_string(),
+        storage::new_uref(timelock_delay).into(),
+    );
+    named_keys.insert("proposal_count".to_string(), storage::new_uref(0u64).into());
+    for dictionary_name in [
+        "proposals",
+        "proposal_votes",
+        "proposal_executions",
+        "receipts",
+    ] {
+        named_keys.insert(
+            dictionary_name.to_string(),
+            storage::new_dictionary(dictionary_name)
+                .unwrap_or_revert()
+                .into(),
+        );
+    }
+    named_keys.insert(
+        "contract_package_hash".to_string(),
+        storage::new_uref(contract_package_hash).into(),
+    );
+
+    let (contract_hash, _) =
+        storage::add_contract_version(contract_package_hash, entry_points::default(), named_keys);
+    runtime::put_key("governor", contract_hash.into());
+    runtime::put_key("governor_package_hash", contract_package_hash.into());
+    runtime::put_key("governor_access_token", access_uref.into());
+}
+
+/// Returns the state of the proposal `proposal_id` at the current block time.
+fn _state(proposal_id: u64) -> ProposalState {
+    let (_, (vote_start, vote_end), _) = _proposal(proposal_id);
+    let (eta, executed, canceled): ProposalExecution =
+        get("proposal_executions", &proposal_id.to_string());
+    let now = u64::from(runtime::get_blocktime());
+
+    if executed {
+        ProposalState::Executed
+    } else if canceled {
+        ProposalState::Canceled
+    } else if now <= vote_start {
+        ProposalState::Pending
+    } else if now <= vote_end {
+        ProposalState::Active
+    } else if !_succeeded(proposal_id) {
+        ProposalState::Defeated
+    } else if eta == 0 {
+        ProposalState::Succeeded
+    } else {
+        ProposalState::Queued
+    }
+}
+
+/// Returns whether the votes for and abstaining reach the quorum and more votes are for than
+/// against the proposal.
+fn _succeeded(proposal_id: u64) -> bool {
+    let (against, for_votes, abstain): ProposalVotes =
+        get("proposal_votes", &proposal_id.to_string());
+    let quorum: U256 = get_key("quorum");
+    for_votes.saturating_add(abstain) >= quorum && for_votes > against
+}
+
+/// Returns the proposal `proposal_id`, reverting if it does not exist.
+fn _proposal(proposal_id: u64) -> Proposal {
+    storage::dictionary_get(_get_uref("proposals"), &proposal_id.to_string())
+        .unwrap_or_revert()
+        .unwrap_or_revert_with(Error::UnknownProposal)
+}
+
+/// Returns the votes `account` had on the token at the end of `timestamp`.
+fn _past_votes(account: Key, timestamp: u64) -> U256 {
+    runtime::call_versioned_contract(
+        get_key::<ContractPackageHash>("token"),
+        None,
+        "get_past_votes",
+        runtime_args! {
+            "account" => account,
+            "timestamp" => timestamp,
+        },
+    )
+}
+
+/// Returns the contract package hash held by `target`.
+fn _target_package(target: Key) -> ContractPackageHash {
+    ContractPackageHash::new(
+        target
+            .into_hash()
+            .unwrap_or_revert_with(Error::InvalidProposalArgs),
+    )
+}
+
+/// Deserializes the arguments of a proposal call.
+fn _call_args(args: &[u8]) -> RuntimeArgs {
+    match RuntimeArgs::from_bytes(args) {
+        Ok((call_args, rest)) if rest.is_empty() => call_args,
+        _ => runtime::revert(Error::InvalidProposalArgs),
+    }
+}
+
+/// Returns the `receipts` item key of the vote of `voter` on `proposal_id`.
+fn _receipt_item_key(proposal_id: u64, voter: Key) -> String {
+    let mut preimage = proposal_id.to_bytes().unwrap_or_revert();
+    preimage.append(&mut voter.to_bytes().unwrap_or_revert());
+    hex::encode(runtime::blake2b(preimage))
+}
+
+fn ret<T: CLTyped + ToBytes>(value: T) {
+    runtime::ret(CLValue::from_t(value).unwrap_or_revert())
+}
+
+fn get_key<T: FromBytes + CLTyped>(name: &str) -> T {
+    storage::read(_get_uref(name))
+        .unwrap_or_revert()
+        .unwrap_or_revert()
+}
+
+fn set_key<T: ToBytes + CLTyped>(name: &str, value: T) {
+    storage::write(_get_uref(name), value)
+}
+
+/// Returns the item `key` of the dictionary `dictionary_name`, or its default if it is missing.
+fn get<T: FromBytes + CLTyped + Default>(dictionary_name: &str, key: &str) -> T {
+    storage::dictionary_get(_get_uref(dictionary_name), key)
+        .unwrap_or_default()
+        .unwrap_or_default()
+}
+
+fn set<T: ToBytes + CLTyped>(dictionary_name: &str, key: &str, value: T) {
+    storage::dictionary_put(_get_uref(dictionary_name), key, value)
+}
+
+fn get_caller() -> Key {
+    let mut callstack = runtime::get_call_stack();
+    callstack.pop();
+    match callstack
+        .last()
+        .ok_or(Error::InvalidContext)
+        .unwrap_or_revert()
+    {
+        CallStackElement::Session { account_hash } => (*account_hash).into(),
+        CallStackElement::StoredSession {
+            account_hash,
+            contract_package_hash: _,
+            contract_hash: _,
+        } => (*account_hash).into(),
+        CallStackElement::StoredContract {
+            contract_package_hash,
+            contract_hash: _,
+        } => Key::from(*contract_package_hash),
+    }
+}
+
+fn _get_uref(name: &str) -> URef {
+    runtime::get_key(name)
+        .unwrap_or_revert()
+        .try_into()
+        .unwrap_or_revert()
+}
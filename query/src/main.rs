@@ -0,0 +1,86 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::{string::String, vec, vec::Vec};
+
+use contract::{
+    contract_api::{runtime, storage},
+    ext_ffi,
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use types::{
+    api_error,
+    bytesrepr::{self, Bytes, ToBytes},
+    contracts::ContractVersion,
+    ContractPackageHash, Key, RuntimeArgs,
+};
+
+/// Named key of the calling account the returned value is stored under.
+const RESULT_KEY: &str = "result";
+
+/// # Purpose
+/// * Calls an entry point of a contract and stores the serialized value it returns under the
+///   `result` named key of the calling account, so the tests can read it back.
+/// # Arguments
+/// * `package` - `Key` -> Contract package hash of the contract.
+/// * `entry_point` - `String` -> Name of the entry point to call.
+/// * `args` - `Bytes` -> Serialized `RuntimeArgs` of the call.
+#[no_mangle]
+pub extern "C" fn call() {
+    let package: Key = runtime::get_named_arg("package");
+    let entry_point: String = runtime::get_named_arg("entry_point");
+    let args: Bytes = runtime::get_named_arg("args");
+
+    let package = ContractPackageHash::new(package.into_hash().unwrap_or_revert());
+    let args: RuntimeArgs = bytesrepr::deserialize(args.into()).unwrap_or_revert();
+
+    let result = call_versioned_contract(package, &entry_point, args);
+    runtime::put_key(RESULT_KEY, storage::new_uref(Bytes::from(result)).into());
+}
+
+/// Calls `entry_point` of the latest version of `package` and returns the bytes of the value
+/// it returned, which `runtime::call_versioned_contract` would deserialize into a known type.
+fn call_versioned_contract(
+    package: ContractPackageHash,
+    entry_point: &str,
+    args: RuntimeArgs,
+) -> Vec<u8> {
+    let package_bytes = package.to_bytes().unwrap_or_revert();
+    let version_bytes = Option::<ContractVersion>::None
+        .to_bytes()
+        .unwrap_or_revert();
+    let entry_point_bytes = entry_point.to_bytes().unwrap_or_revert();
+    let args_bytes = args.to_bytes().unwrap_or_revert();
+
+    let mut result_size = 0usize;
+    let ret = unsafe {
+        ext_ffi::casper_call_versioned_contract(
+            package_bytes.as_ptr(),
+            package_bytes.len(),
+            version_bytes.as_ptr(),
+            version_bytes.len(),
+            entry_point_bytes.as_ptr(),
+            entry_point_bytes.len(),
+            args_bytes.as_ptr(),
+            args_bytes.len(),
+            &mut result_size as *mut usize,
+        )
+    };
+    api_error::result_from(ret).unwrap_or_revert();
+
+    let mut result = vec![0u8; result_size];
+    if result_size > 0 {
+        let mut bytes_written = 0usize;
+        let ret = unsafe {
+            ext_ffi::casper_read_host_buffer(
+                result.as_mut_ptr(),
+                result_size,
+                &mut bytes_written as *mut usize,
+            )
+        };
+        api_error::result_from(ret).unwrap_or_revert();
+    }
+    result
+}
@@ -0,0 +1,245 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::{string::String, vec, vec::Vec};
+use core::convert::TryInto;
+
+use contract::{
+    contract_api::{
+        runtime,
+        storage::{self, create_contract_package_at_hash},
+    },
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use types::{
+    bytesrepr::{Bytes, FromBytes, ToBytes},
+    contracts::NamedKeys,
+    runtime_args,
+    system::CallStackElement,
+    ApiError, CLType, CLTyped, CLValue, ContractPackageHash, EntryPoint, EntryPointAccess,
+    EntryPointType, EntryPoints, Key, Parameter, RuntimeArgs, URef, U256,
+};
+
+/// # Purpose
+/// * Called by the token after `transfer_and_call` credited this contract.
+/// # Arguments
+/// * `operator` - `Key` -> Address that initiated the transfer.
+/// * `from` - `Key` -> Address the tokens were moved from.
+/// * `amount` - `U256` -> Amount of the tokens received.
+/// * `data` - `Bytes` -> Arbitrary data forwarded by the operator.
+/// # Returns
+/// * `accepted` - `bool` -> Whether this contract accepts the tokens.
+#[no_mangle]
+pub extern "C" fn on_transfer_received() {
+    let from: Key = runtime::get_named_arg("from");
+    let amount: U256 = runtime::get_named_arg("amount");
+    let data: Bytes = runtime::get_named_arg("data");
+
+    record(from, amount, data);
+    if read::<bool>("reenter") {
+        runtime::call_versioned_contract::<()>(
+            calling_token(),
+            None,
+            "transfer_and_call",
+            runtime_args! {
+                "recipient_contract" => Key::from(read::<ContractPackageHash>("package")),
+                "amount" => amount,
+                "data" => Bytes::new(),
+            },
+        );
+    }
+    ret(read::<bool>("accept"))
+}
+
+/// # Purpose
+/// * Called by the token after `approve_and_call` granted this contract an allowance.
+/// # Arguments
+/// * `owner` - `Key` -> Address that granted the allowance.
+/// * `amount` - `U256` -> Amount of the allowance.
+/// * `data` - `Bytes` -> Arbitrary data forwarded by the owner.
+/// # Returns
+/// * `accepted` - `bool` -> Whether this contract accepts the allowance.
+#[no_mangle]
+pub extern "C" fn on_approval_received() {
+    let owner: Key = runtime::get_named_arg("owner");
+    let amount: U256 = runtime::get_named_arg("amount");
+    let data: Bytes = runtime::get_named_arg("data");
+
+    record(owner, amount, data);
+    if read::<bool>("reenter") {
+        runtime::call_versioned_contract::<()>(
+            calling_token(),
+            None,
+            "approve_and_call",
+            runtime_args! {
+                "spender_contract" => Key::from(read::<ContractPackageHash>("package")),
+                "amount" => amount,
+                "data" => Bytes::new(),
+            },
+        );
+    }
+    ret(read::<bool>("accept"))
+}
+
+/// # Purpose
+/// * Called by the token after `flash_loan` minted tokens to this contract. Approves the token
+///   to take back the loan plus the fee.
+/// # Arguments
+/// * `initiator` - `Key` -> Address that requested the loan.
+/// * `amount` - `U256` -> Amount of the tokens lent.
+/// * `fee` - `U256` -> Fee owed on top of the amount.
+/// * `data` - `Bytes` -> Arbitrary data forwarded by the initiator.
+/// # Returns
+/// * `accepted` - `bool` -> Whether this contract accepts the loan.
+#[no_mangle]
+pub extern "C" fn on_flash_loan() {
+    let initiator: Key = runtime::get_named_arg("initiator");
+    let amount: U256 = runtime::get_named_arg("amount");
+    let fee: U256 = runtime::get_named_arg("fee");
+    let data: Bytes = runtime::get_named_arg("data");
+
+    record(initiator, amount, data);
+    let token = calling_token();
+    runtime::call_versioned_contract::<()>(
+        token,
+        None,
+        "approve",
+        runtime_args! {
+            "spender" => Key::from(token),
+            "amount" => amount + fee,
+        },
+    );
+    ret(read::<bool>("accept"))
+}
+
+/// # Purpose
+/// * Registers this contract as a token receiver of the given token.
+/// # Arguments
+/// * `token` - `Key` -> Contract package hash of the token.
+#[no_mangle]
+pub extern "C" fn register_with() {
+    let token: Key = runtime::get_named_arg("token");
+    let token_package = ContractPackageHash::new(token.into_hash().unwrap_or_revert());
+    runtime::call_versioned_contract::<()>(
+        token_package,
+        None,
+        "register_receiver",
+        runtime_args! {},
+    );
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let accept: bool = runtime::get_named_arg("accept");
+    let reenter: bool = runtime::get_named_arg("reenter");
+
+    let (contract_package_hash, access_uref) = create_contract_package_at_hash();
+
+    let mut named_keys = NamedKeys::new();
+    named_keys.insert("accept".into(), storage::new_uref(accept).into());
+    named_keys.insert("reenter".into(), storage::new_uref(reenter).into());
+    named_keys.insert(
+        "package".into(),
+        storage::new_uref(contract_package_hash).into(),
+    );
+    named_keys.insert(
+        "last_from".into(),
+        storage::new_uref(Key::Hash([0u8; 32])).into(),
+    );
+    named_keys.insert("last_amount".into(), storage::new_uref(U256::zero()).into());
+    named_keys.insert("last_data".into(), storage::new_uref(Bytes::new()).into());
+
+    let (contract_hash, _) =
+        storage::add_contract_version(contract_package_hash, entry_points(), named_keys);
+    runtime::put_key("receiver", contract_hash.into());
+    runtime::put_key("receiver_package_hash", contract_package_hash.into());
+    runtime::put_key("receiver_access_token", access_uref.into());
+}
+
+fn entry_points() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(endpoint(
+        "on_transfer_received",
+        vec![
+            Parameter::new("operator", CLType::Key),
+            Parameter::new("from", CLType::Key),
+            Parameter::new("amount", CLType::U256),
+            Parameter::new("data", Bytes::cl_type()),
+        ],
+        CLType::Bool,
+    ));
+    entry_points.add_entry_point(endpoint(
+        "on_approval_received",
+        vec![
+            Parameter::new("owner", CLType::Key),
+            Parameter::new("amount", CLType::U256),
+            Parameter::new("data", Bytes::cl_type()),
+        ],
+        CLType::Bool,
+    ));
+    entry_points.add_entry_point(endpoint(
+        "on_flash_loan",
+        vec![
+            Parameter::new("initiator", CLType::Key),
+            Parameter::new("amount", CLType::U256),
+            Parameter::new("fee", CLType::U256),
+            Parameter::new("data", Bytes::cl_type()),
+        ],
+        CLType::Bool,
+    ));
+    entry_points.add_entry_point(endpoint(
+        "register_with",
+        vec![Parameter::new("token", CLType::Key)],
+        CLType::Unit,
+    ));
+    entry_points
+}
+
+fn endpoint(name: &str, params: Vec<Parameter>, ret: CLType) -> EntryPoint {
+    EntryPoint::new(
+        String::from(name),
+        params,
+        ret,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+/// Returns the package of the contract that invoked the running callback.
+fn calling_token() -> ContractPackageHash {
+    let mut call_stack = runtime::get_call_stack();
+    call_stack.pop();
+    match call_stack.last().unwrap_or_revert() {
+        CallStackElement::StoredContract {
+            contract_package_hash,
+            contract_hash: _,
+        } => *contract_package_hash,
+        _ => runtime::revert(ApiError::InvalidCallerInfoRequest),
+    }
+}
+
+/// Remembers the last callback so the tests can assert on it.
+fn record(from: Key, amount: U256, data: Bytes) {
+    storage::write(uref("last_from"), from);
+    storage::write(uref("last_amount"), amount);
+    storage::write(uref("last_data"), data);
+}
+
+fn ret<T: CLTyped + ToBytes>(value: T) {
+    runtime::ret(CLValue::from_t(value).unwrap_or_revert())
+}
+
+fn read<T: CLTyped + FromBytes>(name: &str) -> T {
+    storage::read(uref(name))
+        .unwrap_or_revert()
+        .unwrap_or_revert()
+}
+
+fn uref(name: &str) -> URef {
+    runtime::get_key(name)
+        .unwrap_or_revert()
+        .try_into()
+        .unwrap_or_revert()
+}
@@ -13,12 +13,12 @@ use casper_execution_engine::core::engine_state::{
 };
 use casper_types::{
     account::AccountHash,
-    bytesrepr::{FromBytes, ToBytes},
-    runtime_args, CLTyped, ContractHash, Key, Motes, PublicKey, RuntimeArgs, SecretKey,
-    StoredValue, U256,
+    bytesrepr::{self, Bytes, FromBytes, ToBytes},
+    crypto, runtime_args, CLType, CLTyped, CLValue, ContractHash, Key, Motes, PublicKey,
+    RuntimeArgs, SecretKey, StoredValue, U256, U512,
 };
 use rand::Rng;
-use std::path::PathBuf;
+use std::{collections::BTreeMap, path::PathBuf};
 
 // contains methods that can simulate a real-world deployment (storing the contract in the blockchain)
 // and transactions to invoke the methods in the contract.
@@ -35,8 +35,48 @@ pub mod token_cfg {
 
 pub const ERC20_TOKEN_CONTRACT_KEY_NAME: &str = "erc20_token_contract";
 
+/// The contract built by `make build-contract`.
+pub const ERC20_WASM: &str = "erc20.wasm";
+
+/// Build of the contract before the storage access path was reworked, kept to compare gas costs.
+pub const ERC20_BASELINE_WASM: &str = "erc20_baseline.wasm";
+
+/// Contract implementing the token receiver callbacks.
+pub const RECEIVER_WASM: &str = "receiver.wasm";
+const RECEIVER_CONTRACT_KEY_NAME: &str = "receiver";
+const RECEIVER_PACKAGE_KEY_NAME: &str = "receiver_package_hash";
+
+/// Governor contract voting with the token's delegated votes.
+pub const GOVERNOR_WASM: &str = "governor.wasm";
+const GOVERNOR_CONTRACT_KEY_NAME: &str = "governor";
+const GOVERNOR_PACKAGE_KEY_NAME: &str = "governor_package_hash";
+
+/// Session code storing the value returned by an entry point under the `result` named key.
+pub const QUERY_WASM: &str = "query.wasm";
+const QUERY_RESULT_KEY_NAME: &str = "result";
+
+/// Upper bound for the size of the stripped `erc20.wasm`, in bytes.
+pub const ERC20_WASM_SIZE_BUDGET: u64 = 100 * 1024;
+
 const BALANCES_DICT: &str = "balances";
 const ALLOWANCES_DICT: &str = "allowances";
+const METADATA_DICT: &str = "metadata";
+const OPERATORS_DICT: &str = "operators";
+const SPENDERS_DICT: &str = "spenders";
+const NONCES_DICT: &str = "nonces";
+const AUTHORIZATION_STATES_DICT: &str = "authorization_states";
+const DELEGATES_DICT: &str = "delegates";
+
+/// Prefix of the domain separator of the contract.
+const DOMAIN_TAG: &[u8] = b"casper-erc20";
+/// Tag of the messages signed for `permit`.
+const PERMIT_TAG: &[u8] = b"permit";
+/// Tag of the messages signed for `transfer_with_authorization`.
+const TRANSFER_AUTHORIZATION_TAG: &[u8] = b"transfer_with_authorization";
+/// Tag of the messages signed for `cancel_authorization`.
+const CANCEL_AUTHORIZATION_TAG: &[u8] = b"cancel_authorization";
+/// Tag of the messages signed for `delegate_by_sig`.
+const DELEGATION_TAG: &[u8] = b"delegate";
 
 pub struct Sender(pub AccountHash);
 pub type Hash = [u8; 32];
@@ -159,13 +199,244 @@ pub struct Token {
     pub symbol: String,
     pub builder: InMemoryWasmTestBuilder,
     pub hash: Hash,
+    pub contract_key: String,
     pub ali: AccountHash,
     pub bob: AccountHash,
     pub joe: AccountHash,
+    /// Block time, in milliseconds, of the next calls to the contract.
+    pub block_time: u64,
 }
 
 impl Token {
     pub fn deployed(name: &str, symbol: &str) -> Token {
+        let session_args = runtime_args! {
+            "name" => name,
+            "symbol" => symbol,
+            "decimals" => token_cfg::DECIMALS,
+            "total_supply" => token_cfg::total_supply(),
+        };
+        Token::install(
+            "erc20_token.wasm",
+            session_args,
+            name,
+            symbol,
+            ERC20_TOKEN_CONTRACT_KEY_NAME,
+        )
+    }
+
+    /// Deploys this repository's contract from the given `wasm` file.
+    pub fn deployed_from(wasm: &str, name: &str, symbol: &str) -> Token {
+        Token::deployed_with_decimals(wasm, name, symbol, token_cfg::DECIMALS)
+    }
+
+    pub fn deployed_with_decimals(wasm: &str, name: &str, symbol: &str, decimals: u8) -> Token {
+        let session_args = Token::install_args(name, symbol, decimals);
+        Token::install(wasm, session_args, name, symbol, name)
+    }
+
+    /// Installs another copy of the contract from `wasm`, deployed by `ali` on the same chain.
+    pub fn deploy_another(&mut self, wasm: &str, name: &str, symbol: &str) -> Hash {
+        let ali = PublicKey::from(&SecretKey::ed25519_from_bytes([3u8; 32]).unwrap());
+        let builder = std::mem::take(&mut self.builder);
+        let (builder, hash) = Config::deploy_contract(
+            builder,
+            PathBuf::from(wasm),
+            Token::install_args(name, symbol, token_cfg::DECIMALS),
+            ali,
+            name.to_string(),
+        );
+        self.builder = builder;
+        hash
+    }
+
+    /// Installs the receiver contract, deployed by `ali`, and returns its contract package key.
+    pub fn deploy_receiver(&mut self, accept: bool) -> Key {
+        self.deploy_receiver_with(accept, false)
+    }
+
+    /// Installs a receiver that calls back into the token from its callbacks.
+    pub fn deploy_malicious_receiver(&mut self) -> Key {
+        self.deploy_receiver_with(true, true)
+    }
+
+    fn deploy_receiver_with(&mut self, accept: bool, reenter: bool) -> Key {
+        let ali = PublicKey::from(&SecretKey::ed25519_from_bytes([3u8; 32]).unwrap());
+        let builder = std::mem::take(&mut self.builder);
+        let (builder, _) = Config::deploy_contract(
+            builder,
+            PathBuf::from(RECEIVER_WASM),
+            runtime_args! {
+                "accept" => accept,
+                "reenter" => reenter,
+            },
+            ali,
+            RECEIVER_CONTRACT_KEY_NAME.to_string(),
+        );
+        self.builder = builder;
+        self.named_key(RECEIVER_PACKAGE_KEY_NAME)
+    }
+
+    /// query a named key of the receiver contract.
+    pub fn query_receiver<T: CLTyped + FromBytes>(&self, name: &str) -> T {
+        self.builder
+            .query(
+                None,
+                Key::Account(self.ali),
+                &[RECEIVER_CONTRACT_KEY_NAME.to_string(), name.to_string()],
+            )
+            .expect("should have receiver named key")
+            .as_cl_value()
+            .expect("should be cl value.")
+            .clone()
+            .into_t()
+            .expect("should have the correct type.")
+    }
+
+    /// Installs a governor of the token, deployed by `ali`, and returns its contract package key.
+    pub fn deploy_governor(
+        &mut self,
+        proposal_threshold: U256,
+        quorum: U256,
+        (voting_delay, voting_period, timelock_delay): (u64, u64, u64),
+    ) -> Key {
+        let ali = PublicKey::from(&SecretKey::ed25519_from_bytes([3u8; 32]).unwrap());
+        let token = self.package_key();
+        let builder = std::mem::take(&mut self.builder);
+        let (builder, _) = Config::deploy_contract(
+            builder,
+            PathBuf::from(GOVERNOR_WASM),
+            runtime_args! {
+                "token" => token,
+                "proposal_threshold" => proposal_threshold,
+                "quorum" => quorum,
+                "voting_delay" => voting_delay,
+                "voting_period" => voting_period,
+                "timelock_delay" => timelock_delay,
+            },
+            ali,
+            GOVERNOR_CONTRACT_KEY_NAME.to_string(),
+        );
+        self.builder = builder;
+        self.named_key(GOVERNOR_PACKAGE_KEY_NAME)
+    }
+
+    fn governor_hash(&self) -> Hash {
+        self.named_key(GOVERNOR_CONTRACT_KEY_NAME)
+            .into_hash()
+            .expect("should have governor contract hash")
+    }
+
+    /// Proposes to call `entry_point` of `target` with `args` through the governor.
+    pub fn propose(&mut self, target: Key, entry_point: &str, args: RuntimeArgs, sender: Sender) {
+        let governor = self.governor_hash();
+        self.call_contract(
+            sender,
+            governor,
+            "propose",
+            runtime_args! {
+                "target" => target,
+                "entry_point" => entry_point.to_string(),
+                "args" => Bytes::from(args.to_bytes().unwrap()),
+                "description" => format!("Call {}", entry_point),
+            },
+        );
+    }
+
+    pub fn cast_vote(&mut self, proposal_id: u64, support: u8, sender: Sender) {
+        let governor = self.governor_hash();
+        self.call_contract(
+            sender,
+            governor,
+            "cast_vote",
+            runtime_args! {
+                "proposal_id" => proposal_id,
+                "support" => support,
+            },
+        );
+    }
+
+    /// Calls the governor entry point `method` that only takes the proposal id.
+    pub fn call_governor(&mut self, method: &str, proposal_id: u64, sender: Sender) {
+        let governor = self.governor_hash();
+        self.call_contract(
+            sender,
+            governor,
+            method,
+            runtime_args! {
+                "proposal_id" => proposal_id,
+            },
+        );
+    }
+
+    /// Returns the votes `(against, for, abstain)` cast on a proposal.
+    pub fn proposal_votes(&self, proposal_id: u64) -> (U256, U256, U256) {
+        self.query_dictionary_value_of(
+            self.governor_hash(),
+            "proposal_votes",
+            proposal_id.to_string(),
+        )
+        .unwrap_or_default()
+    }
+
+    /// Returns the `(eta, executed, canceled)` progress of a proposal.
+    pub fn proposal_execution(&self, proposal_id: u64) -> (u64, bool, bool) {
+        self.query_dictionary_value_of(
+            self.governor_hash(),
+            "proposal_executions",
+            proposal_id.to_string(),
+        )
+        .unwrap_or_default()
+    }
+
+    /// Returns the contract package key of the token.
+    pub fn package_key(&self) -> Key {
+        self.named_key(&format!("{}_package_hash", self.contract_key))
+    }
+
+    /// Makes the receiver contract register itself with the token.
+    pub fn register_receiver(&mut self, sender: Sender) {
+        let receiver = self
+            .named_key(RECEIVER_CONTRACT_KEY_NAME)
+            .into_hash()
+            .expect("should have receiver contract hash");
+        let token = self.package_key();
+        self.call_contract(
+            sender,
+            receiver,
+            "register_with",
+            runtime_args! {
+                "token" => token
+            },
+        );
+    }
+
+    /// Returns a named key of `ali`, who deploys every contract in these tests.
+    fn named_key(&self, name: &str) -> Key {
+        *self
+            .builder
+            .get_account(self.ali)
+            .expect("should have account")
+            .named_keys()
+            .get(name)
+            .expect("should have named key")
+    }
+
+    fn install_args(name: &str, symbol: &str, decimals: u8) -> RuntimeArgs {
+        runtime_args! {
+            "token_name" => name,
+            "token_symbol" => symbol,
+            "token_decimals" => decimals,
+            "token_total_supply" => token_cfg::total_supply(),
+        }
+    }
+
+    fn install(
+        wasm: &str,
+        session_args: RuntimeArgs,
+        name: &str,
+        symbol: &str,
+        contract_key: &str,
+    ) -> Token {
         // ====================== ACCOUNTS SETUP ======================
         let ali = PublicKey::from(&SecretKey::ed25519_from_bytes([3u8; 32]).unwrap());
         let bob = PublicKey::from(&SecretKey::ed25519_from_bytes([6u8; 32]).unwrap());
@@ -179,7 +450,7 @@ impl Token {
 
         // implement custom accounts.
         let genesis_accounts: Vec<GenesisAccount> =
-            Config::set_custom_accounts(vec![ali.clone(), bob.clone()]);
+            Config::set_custom_accounts(vec![ali.clone(), bob.clone(), joe.clone()]);
 
         // implement custom exec config.
         let custom_exec_config: ExecConfig = Config::set_custom_exec_config(genesis_accounts);
@@ -192,20 +463,14 @@ impl Token {
         builder.run_genesis(&custom_run_genesis_request).commit();
 
         // ====================== CONTRACT DEPLOYMENT ======================
-        let session_code = PathBuf::from("erc20_token.wasm");
-        let session_args = runtime_args! {
-            "name" => name,
-            "symbol" => symbol,
-            "decimals" => token_cfg::DECIMALS,
-            "total_supply" => token_cfg::total_supply(),
-        };
+        let session_code = PathBuf::from(wasm);
 
         let (builder, hash) = Config::deploy_contract(
             builder,
             session_code,
             session_args,
             ali.clone(),
-            ERC20_TOKEN_CONTRACT_KEY_NAME.to_string(),
+            contract_key.to_string(),
         );
 
         // ====================== FUNCTION RETURN ======================
@@ -214,9 +479,11 @@ impl Token {
             symbol: symbol.to_string(),
             builder,
             hash,
+            contract_key: contract_key.to_string(),
             ali: ali.to_account_hash(),
             bob: bob.to_account_hash(),
             joe: joe.to_account_hash(),
+            block_time: 0,
         }
     }
 
@@ -225,7 +492,7 @@ impl Token {
         match self.builder.query(
             None,
             Key::Account(self.ali),
-            &[ERC20_TOKEN_CONTRACT_KEY_NAME.to_string(), name.to_string()],
+            &[self.contract_key.clone(), name.to_string()],
         ) {
             Err(_) => None,
             Ok(maybe_value) => {
@@ -245,10 +512,34 @@ impl Token {
         dict_name: &str,
         key: String,
     ) -> Option<T> {
+        self.query_dictionary_value_of(self.hash, dict_name, key)
+    }
+
+    fn query_dictionary_value_of<T: CLTyped + FromBytes>(
+        &self,
+        contract: Hash,
+        dict_name: &str,
+        key: String,
+    ) -> Option<T> {
+        self.query_dictionary_cl_value_of(contract, dict_name, key)
+            .map(|value| value.into_t().expect("should have the correct type."))
+    }
+
+    fn query_dictionary_cl_value(&self, dict_name: &str, key: String) -> Option<CLValue> {
+        self.query_dictionary_cl_value_of(self.hash, dict_name, key)
+    }
+
+    /// query a dictionary of the contract stored under `contract`.
+    fn query_dictionary_cl_value_of(
+        &self,
+        contract: Hash,
+        dict_name: &str,
+        key: String,
+    ) -> Option<CLValue> {
         // prepare the dictionary seed uref.
         let stored_value = self
             .builder
-            .query(None, Key::Hash(self.hash), &[])
+            .query(None, Key::Hash(contract), &[])
             .map_err(|_| "error")
             .unwrap();
 
@@ -276,9 +567,7 @@ impl Token {
                 let value = maybe_value
                     .as_cl_value()
                     .expect("should be cl value.")
-                    .clone()
-                    .into_t()
-                    .expect("should have the correct type.");
+                    .clone();
                 Some(value)
             }
         }
@@ -286,6 +575,11 @@ impl Token {
 
     /// call a contract's specific entry point.
     fn call(&mut self, sender: Sender, method: &str, args: RuntimeArgs) {
+        self.call_contract(sender, self.hash, method, args);
+    }
+
+    /// call a specific entry point of the contract stored under `hash`.
+    fn call_contract(&mut self, sender: Sender, hash: Hash, method: &str, args: RuntimeArgs) {
         let Sender(address) = sender;
 
         // prepare the deploy item.
@@ -294,19 +588,70 @@ impl Token {
             .with_empty_payment_bytes(runtime_args! {
                 ARG_AMOUNT => *DEFAULT_PAYMENT
             })
-            .with_stored_session_hash(self.hash.into(), method, args)
+            .with_stored_session_hash(hash.into(), method, args)
             .with_authorization_keys(&[address])
             .with_address(address)
             .build();
 
         // prepare the execute request.
-        // we can use .with_block_time() when setting the execute request.
-        let execute_request = ExecuteRequestBuilder::from_deploy_item(deploy_item).build();
+        let execute_request = ExecuteRequestBuilder::from_deploy_item(deploy_item)
+            .with_block_time(self.block_time)
+            .build();
 
         // executes the execute_request.
         self.builder.exec(execute_request).commit().expect_success();
     }
 
+    /// Calls `method` of the contract from the query session of `sender` and returns the value
+    /// it returned.
+    fn query<T: FromBytes>(&mut self, sender: Sender, method: &str, args: RuntimeArgs) -> T {
+        let Sender(address) = sender;
+        let mut rng = rand::thread_rng();
+
+        let deploy_item = DeployItemBuilder::new()
+            .with_empty_payment_bytes(runtime_args! {
+                ARG_AMOUNT => *DEFAULT_PAYMENT
+            })
+            .with_session_code(
+                PathBuf::from(QUERY_WASM),
+                runtime_args! {
+                    "package" => self.package_key(),
+                    "entry_point" => method.to_string(),
+                    "args" => Bytes::from(args.to_bytes().unwrap()),
+                },
+            )
+            .with_deploy_hash(rng.gen())
+            .with_authorization_keys(&[address])
+            .with_address(address)
+            .build();
+
+        let execute_request = ExecuteRequestBuilder::from_deploy_item(deploy_item)
+            .with_block_time(self.block_time)
+            .build();
+
+        self.builder.exec(execute_request).commit().expect_success();
+
+        let result: Bytes = self
+            .builder
+            .query(
+                None,
+                Key::Account(address),
+                &[QUERY_RESULT_KEY_NAME.to_string()],
+            )
+            .expect("should have query result")
+            .as_cl_value()
+            .expect("should be cl value.")
+            .clone()
+            .into_t()
+            .expect("should have the correct type.");
+        bytesrepr::deserialize(result.into()).expect("should have the correct type.")
+    }
+
+    /// Returns the gas spent by the last executed deploy.
+    pub fn last_gas_cost(&self) -> U512 {
+        self.builder.last_exec_gas_cost().value()
+    }
+
     pub fn name(&self) -> String {
         self.query_contract("name").unwrap()
     }
@@ -328,6 +673,76 @@ impl Token {
             .unwrap()
     }
 
+    pub fn balance_of_at(&mut self, address: Key, id: u64) -> U256 {
+        self.query(
+            Sender(self.ali),
+            "balance_of_at",
+            runtime_args! {
+                "address" => address,
+                "id" => id
+            },
+        )
+    }
+
+    pub fn total_supply_at(&mut self, id: u64) -> U256 {
+        self.query(
+            Sender(self.ali),
+            "total_supply_at",
+            runtime_args! {
+                "id" => id
+            },
+        )
+    }
+
+    pub fn is_blacklisted(&mut self, account: Key) -> bool {
+        self.query(
+            Sender(self.ali),
+            "is_blacklisted",
+            runtime_args! {
+                "account" => account
+            },
+        )
+    }
+
+    /// Whether `account` is allowlisted at the block time of the next call.
+    pub fn is_allowlisted(&mut self, account: Key) -> bool {
+        self.query(
+            Sender(self.ali),
+            "is_allowlisted",
+            runtime_args! {
+                "account" => account
+            },
+        )
+    }
+
+    pub fn delegates(&self, account: Key) -> Option<Key> {
+        self.query_dictionary_value(DELEGATES_DICT, key_to_str(&account))
+            .unwrap_or_default()
+    }
+
+    pub fn get_votes(&mut self, account: Key) -> U256 {
+        self.query(
+            Sender(self.ali),
+            "get_votes",
+            runtime_args! {
+                "account" => account
+            },
+        )
+    }
+
+    /// Returns the votes of `account` at the end of `timestamp`, which must be before the block
+    /// time of the next call.
+    pub fn get_past_votes(&mut self, account: Key, timestamp: u64) -> U256 {
+        self.query(
+            Sender(self.ali),
+            "get_past_votes",
+            runtime_args! {
+                "account" => account,
+                "timestamp" => timestamp
+            },
+        )
+    }
+
     pub fn allowance(&self, owner: Key, spender: Key) -> U256 {
         let mut preimage = Vec::new();
         preimage.append(&mut owner.to_bytes().unwrap());
@@ -335,8 +750,119 @@ impl Token {
         let key_bytes = blake2b256(&preimage);
         let allowance_item_key = hex::encode(&key_bytes);
 
-        self.query_dictionary_value(ALLOWANCES_DICT, allowance_item_key)
-            .unwrap()
+        let value = self
+            .query_dictionary_cl_value(ALLOWANCES_DICT, allowance_item_key)
+            .unwrap();
+
+        // this repository's contract stores the deadline next to the amount.
+        match value.cl_type() {
+            CLType::U256 => value.into_t().unwrap(),
+            _ => value.into_t::<(U256, u64)>().unwrap().0,
+        }
+    }
+
+    pub fn spenders_of(&self, owner: Key) -> Vec<Key> {
+        self.query_dictionary_value(SPENDERS_DICT, key_to_str(&owner))
+            .unwrap_or_default()
+    }
+
+    pub fn nonces(&self, owner: Key) -> u64 {
+        self.query_dictionary_value(NONCES_DICT, key_to_str(&owner))
+            .unwrap_or_default()
+    }
+
+    /// Signs the `permit` message for the account of `secret_key`, using its current nonce.
+    pub fn sign_permit(
+        &self,
+        secret_key: &SecretKey,
+        spender: Key,
+        amount: U256,
+        deadline: u64,
+    ) -> Bytes {
+        let public_key = PublicKey::from(secret_key);
+        let owner = Key::Account(public_key.to_account_hash());
+        let fields = (public_key, spender, amount, self.nonces(owner), deadline)
+            .to_bytes()
+            .unwrap();
+        self.sign(secret_key, PERMIT_TAG, &fields)
+    }
+
+    /// Signs a `delegate_by_sig` message for the account of `secret_key`, using its current nonce.
+    pub fn sign_delegation(&self, secret_key: &SecretKey, delegatee: Key, expiry: u64) -> Bytes {
+        let public_key = PublicKey::from(secret_key);
+        let delegator = Key::Account(public_key.to_account_hash());
+        let fields = (public_key, delegatee, self.nonces(delegator), expiry)
+            .to_bytes()
+            .unwrap();
+        self.sign(secret_key, DELEGATION_TAG, &fields)
+    }
+
+    /// Signs a `transfer_with_authorization` message for the account of `secret_key`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn sign_transfer_authorization(
+        &self,
+        secret_key: &SecretKey,
+        to: Key,
+        amount: U256,
+        fee: U256,
+        valid_after: u64,
+        valid_before: u64,
+        nonce: [u8; 32],
+    ) -> Bytes {
+        let public_key = PublicKey::from(secret_key);
+        let fields = (
+            public_key,
+            to,
+            amount,
+            fee,
+            valid_after,
+            valid_before,
+            nonce,
+        )
+            .to_bytes()
+            .unwrap();
+        self.sign(secret_key, TRANSFER_AUTHORIZATION_TAG, &fields)
+    }
+
+    /// Signs a `cancel_authorization` message for the account of `secret_key`.
+    pub fn sign_cancel_authorization(&self, secret_key: &SecretKey, nonce: [u8; 32]) -> Bytes {
+        let fields = (PublicKey::from(secret_key), nonce).to_bytes().unwrap();
+        self.sign(secret_key, CANCEL_AUTHORIZATION_TAG, &fields)
+    }
+
+    pub fn authorization_state(&self, authorizer: Key, nonce: [u8; 32]) -> bool {
+        let mut preimage = authorizer.to_bytes().unwrap();
+        preimage.extend_from_slice(&nonce);
+        let item_key = hex::encode(&blake2b256(&preimage));
+
+        self.query_dictionary_value(AUTHORIZATION_STATES_DICT, item_key)
+            .unwrap_or_default()
+    }
+
+    /// Signs the blake2b hash of the domain separator, `tag` and `fields` the way the contract
+    /// expects it: as the raw 64 signature bytes, without the algorithm tag.
+    fn sign(&self, secret_key: &SecretKey, tag: &[u8], fields: &[u8]) -> Bytes {
+        let package = self.package_key().into_hash().unwrap();
+        let mut domain = DOMAIN_TAG.to_vec();
+        domain.extend_from_slice(&package);
+        let mut preimage = blake2b256(&domain).to_vec();
+        preimage.extend_from_slice(tag);
+        preimage.extend_from_slice(fields);
+        let message = blake2b256(&preimage);
+
+        let public_key = PublicKey::from(secret_key);
+        let signature = crypto::sign(&message, secret_key, &public_key);
+        Bytes::from(signature.to_bytes().unwrap()[1..].to_vec())
+    }
+
+    pub fn is_operator_for(&self, operator: Key, holder: Key) -> bool {
+        let mut preimage = Vec::new();
+        preimage.append(&mut holder.to_bytes().unwrap());
+        preimage.append(&mut operator.to_bytes().unwrap());
+        let operator_item_key = hex::encode(&blake2b256(&preimage));
+
+        self.query_dictionary_value(OPERATORS_DICT, operator_item_key)
+            .unwrap_or_default()
     }
 
     pub fn transfer(&mut self, recipient: Key, amount: U256, sender: Sender) {
@@ -361,6 +887,252 @@ impl Token {
         );
     }
 
+    pub fn permit(
+        &mut self,
+        owner_public_key: PublicKey,
+        spender: Key,
+        amount: U256,
+        deadline: u64,
+        signature: Bytes,
+        sender: Sender,
+    ) {
+        self.call(
+            sender,
+            "permit",
+            runtime_args! {
+                "owner_public_key" => owner_public_key,
+                "spender" => spender,
+                "amount" => amount,
+                "deadline" => deadline,
+                "signature" => signature
+            },
+        );
+    }
+
+    /// Submits a signed transfer; the relayer `fee` is left out of the call when it is zero.
+    #[allow(clippy::too_many_arguments)]
+    pub fn transfer_with_authorization(
+        &mut self,
+        from_public_key: PublicKey,
+        to: Key,
+        amount: U256,
+        fee: U256,
+        (valid_after, valid_before): (u64, u64),
+        nonce: [u8; 32],
+        signature: Bytes,
+        sender: Sender,
+    ) {
+        let mut args = runtime_args! {
+            "from_public_key" => from_public_key,
+            "to" => to,
+            "amount" => amount,
+            "valid_after" => valid_after,
+            "valid_before" => valid_before,
+            "nonce" => nonce,
+            "signature" => signature
+        };
+        if !fee.is_zero() {
+            args.insert("fee", fee).unwrap();
+        }
+        self.call(sender, "transfer_with_authorization", args);
+    }
+
+    pub fn cancel_authorization(
+        &mut self,
+        from_public_key: PublicKey,
+        nonce: [u8; 32],
+        signature: Bytes,
+        sender: Sender,
+    ) {
+        self.call(
+            sender,
+            "cancel_authorization",
+            runtime_args! {
+                "from_public_key" => from_public_key,
+                "nonce" => nonce,
+                "signature" => signature
+            },
+        );
+    }
+
+    pub fn approve_with_expiry(
+        &mut self,
+        spender: Key,
+        amount: U256,
+        expires_at: u64,
+        sender: Sender,
+    ) {
+        self.call(
+            sender,
+            "approve_with_expiry",
+            runtime_args! {
+                "spender" => spender,
+                "amount" => amount,
+                "expires_at" => expires_at
+            },
+        );
+    }
+
+    pub fn approve_recurring(
+        &mut self,
+        spender: Key,
+        amount_per_period: U256,
+        period_ms: u64,
+        sender: Sender,
+    ) {
+        self.call(
+            sender,
+            "approve_recurring",
+            runtime_args! {
+                "spender" => spender,
+                "amount_per_period" => amount_per_period,
+                "period_ms" => period_ms
+            },
+        );
+    }
+
+    pub fn collect(&mut self, owner: Key, recipient: Key, amount: U256, sender: Sender) {
+        self.call(
+            sender,
+            "collect",
+            runtime_args! {
+                "owner" => owner,
+                "recipient" => recipient,
+                "amount" => amount
+            },
+        );
+    }
+
+    pub fn delegate(&mut self, delegatee: Key, sender: Sender) {
+        self.call(
+            sender,
+            "delegate",
+            runtime_args! {
+                "delegatee" => delegatee
+            },
+        );
+    }
+
+    pub fn delegate_by_sig(
+        &mut self,
+        delegator_public_key: PublicKey,
+        delegatee: Key,
+        expiry: u64,
+        signature: Bytes,
+        sender: Sender,
+    ) {
+        self.call(
+            sender,
+            "delegate_by_sig",
+            runtime_args! {
+                "delegator_public_key" => delegator_public_key,
+                "delegatee" => delegatee,
+                "expiry" => expiry,
+                "signature" => signature
+            },
+        );
+    }
+
+    pub fn flash_loan(&mut self, receiver: Key, amount: U256, data: Bytes, sender: Sender) {
+        self.call(
+            sender,
+            "flash_loan",
+            runtime_args! {
+                "receiver" => receiver,
+                "amount" => amount,
+                "data" => data
+            },
+        );
+    }
+
+    pub fn set_flash_fee(&mut self, basis_points: u64, sender: Sender) {
+        self.call(
+            sender,
+            "set_flash_fee",
+            runtime_args! {
+                "basis_points" => basis_points
+            },
+        );
+    }
+
+    pub fn set_flash_fee_recipient(&mut self, recipient: Option<Key>, sender: Sender) {
+        self.call(
+            sender,
+            "set_flash_fee_recipient",
+            runtime_args! {
+                "recipient" => recipient
+            },
+        );
+    }
+
+    pub fn set_allowlist_required(&mut self, enabled: bool, sender: Sender) {
+        self.call(
+            sender,
+            "set_allowlist_required",
+            runtime_args! {
+                "enabled" => enabled
+            },
+        );
+    }
+
+    pub fn grant_kyc_role(&mut self, account: Key, sender: Sender) {
+        self.call(
+            sender,
+            "grant_kyc_role",
+            runtime_args! {
+                "account" => account
+            },
+        );
+    }
+
+    pub fn allowlist_add(&mut self, accounts: Vec<(Key, Option<u64>)>, sender: Sender) {
+        self.call(
+            sender,
+            "allowlist_add",
+            runtime_args! {
+                "accounts" => accounts
+            },
+        );
+    }
+
+    pub fn allowlist_remove(&mut self, accounts: Vec<Key>, sender: Sender) {
+        self.call(
+            sender,
+            "allowlist_remove",
+            runtime_args! {
+                "accounts" => accounts
+            },
+        );
+    }
+
+    pub fn blacklist(&mut self, account: Key, sender: Sender) {
+        self.call(
+            sender,
+            "blacklist",
+            runtime_args! {
+                "account" => account
+            },
+        );
+    }
+
+    pub fn unblacklist(&mut self, account: Key, sender: Sender) {
+        self.call(
+            sender,
+            "unblacklist",
+            runtime_args! {
+                "account" => account
+            },
+        );
+    }
+
+    pub fn snapshot(&mut self, sender: Sender) {
+        self.call(sender, "snapshot", runtime_args! {});
+    }
+
+    pub fn revoke_all_allowances(&mut self, sender: Sender) {
+        self.call(sender, "revoke_all_allowances", runtime_args! {});
+    }
+
     pub fn transfer_from(&mut self, owner: Key, recipient: Key, amount: U256, sender: Sender) {
         self.call(
             sender,
@@ -372,4 +1144,219 @@ impl Token {
             },
         );
     }
+
+    pub fn metadata(&self, key: &str) -> Option<String> {
+        self.query_dictionary_value(METADATA_DICT, key.to_string())
+    }
+
+    pub fn transfer_with_memo(&mut self, recipient: Key, amount: U256, memo: &str, sender: Sender) {
+        self.call(
+            sender,
+            "transfer_with_memo",
+            runtime_args! {
+                "recipient" => recipient,
+                "amount" => amount,
+                "memo" => memo
+            },
+        );
+    }
+
+    pub fn transfer_from_with_memo(
+        &mut self,
+        owner: Key,
+        recipient: Key,
+        amount: U256,
+        memo: &str,
+        sender: Sender,
+    ) {
+        self.call(
+            sender,
+            "transfer_from_with_memo",
+            runtime_args! {
+                "owner" => owner,
+                "recipient" => recipient,
+                "amount" => amount,
+                "memo" => memo
+            },
+        );
+    }
+
+    pub fn transfer_and_call(
+        &mut self,
+        recipient_contract: Key,
+        amount: U256,
+        data: Bytes,
+        sender: Sender,
+    ) {
+        self.call(
+            sender,
+            "transfer_and_call",
+            runtime_args! {
+                "recipient_contract" => recipient_contract,
+                "amount" => amount,
+                "data" => data
+            },
+        );
+    }
+
+    pub fn approve_and_call(
+        &mut self,
+        spender_contract: Key,
+        amount: U256,
+        data: Bytes,
+        sender: Sender,
+    ) {
+        self.call(
+            sender,
+            "approve_and_call",
+            runtime_args! {
+                "spender_contract" => spender_contract,
+                "amount" => amount,
+                "data" => data
+            },
+        );
+    }
+
+    pub fn authorize_operator(&mut self, operator: Key, sender: Sender) {
+        self.call(
+            sender,
+            "authorize_operator",
+            runtime_args! {
+                "operator" => operator
+            },
+        );
+    }
+
+    pub fn revoke_operator(&mut self, operator: Key, sender: Sender) {
+        self.call(
+            sender,
+            "revoke_operator",
+            runtime_args! {
+                "operator" => operator
+            },
+        );
+    }
+
+    pub fn operator_send(&mut self, holder: Key, recipient: Key, amount: U256, sender: Sender) {
+        self.call(
+            sender,
+            "operator_send",
+            runtime_args! {
+                "holder" => holder,
+                "recipient" => recipient,
+                "amount" => amount
+            },
+        );
+    }
+
+    pub fn set_safe_transfers(&mut self, enabled: bool, sender: Sender) {
+        self.call(
+            sender,
+            "set_safe_transfers",
+            runtime_args! {
+                "enabled" => enabled
+            },
+        );
+    }
+
+    pub fn batch_transfer(&mut self, recipients: Vec<(Key, U256)>, sender: Sender) {
+        self.call(
+            sender,
+            "batch_transfer",
+            runtime_args! {
+                "recipients" => recipients
+            },
+        );
+    }
+
+    pub fn batch_transfer_from(&mut self, items: Vec<(Key, Key, U256)>, sender: Sender) {
+        self.call(
+            sender,
+            "batch_transfer_from",
+            runtime_args! {
+                "items" => items
+            },
+        );
+    }
+
+    pub fn detect_transfer_restriction(&mut self, from: Key, to: Key, amount: U256) -> u8 {
+        self.query(
+            Sender(self.ali),
+            "detect_transfer_restriction",
+            runtime_args! {
+                "from" => from,
+                "to" => to,
+                "amount" => amount
+            },
+        )
+    }
+
+    pub fn message_for_transfer_restriction(&mut self, restriction_code: u8) -> String {
+        self.query(
+            Sender(self.ali),
+            "message_for_transfer_restriction",
+            runtime_args! {
+                "restriction_code" => restriction_code
+            },
+        )
+    }
+
+    pub fn balances_of(&mut self, addresses: Vec<Key>, sender: Sender) -> Vec<U256> {
+        self.query(
+            sender,
+            "balances_of",
+            runtime_args! {
+                "addresses" => addresses
+            },
+        )
+    }
+
+    pub fn allowances_of(&mut self, pairs: Vec<(Key, Key)>, sender: Sender) -> Vec<U256> {
+        self.query(
+            sender,
+            "allowances_of",
+            runtime_args! {
+                "pairs" => pairs
+            },
+        )
+    }
+
+    /// Returns `((name, symbol, decimals), total_supply, metadata)`.
+    pub fn token_info(
+        &mut self,
+        sender: Sender,
+    ) -> ((String, String, u8), U256, BTreeMap<String, String>) {
+        self.query(sender, "token_info", runtime_args! {})
+    }
+
+    pub fn set_metadata(&mut self, key: &str, value: &str, sender: Sender) {
+        self.call(
+            sender,
+            "set_metadata",
+            runtime_args! {
+                "key" => key,
+                "value" => value
+            },
+        );
+    }
+
+    pub fn set_name(&mut self, name: &str, sender: Sender) {
+        self.call(
+            sender,
+            "set_name",
+            runtime_args! {
+                "name" => name
+            },
+        );
+    }
+
+    pub fn set_symbol(&mut self, symbol: &str, sender: Sender) {
+        self.call(
+            sender,
+            "set_symbol",
+            runtime_args! {
+                "symbol" => symbol
+            },
+        );
+    }
 }
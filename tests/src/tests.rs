@@ -1,6 +1,13 @@
-use casper_types::{account::AccountHash, Key, U256};
+use std::{fs, path::PathBuf};
 
-use crate::erc20::{token_cfg, Sender, Token};
+use casper_types::{
+    account::AccountHash, bytesrepr::Bytes, runtime_args, Key, PublicKey, RuntimeArgs, SecretKey,
+    U256, U512,
+};
+
+use crate::erc20::{
+    token_cfg, Sender, Token, ERC20_BASELINE_WASM, ERC20_WASM, ERC20_WASM_SIZE_BUDGET,
+};
 
 fn to_key(account: AccountHash) -> Key {
     Key::Account(account)
@@ -107,3 +114,1427 @@ fn should_not_transfer_from_erc20_with_low_allowance() {
     t.approve(to_key(t.bob), allowance, Sender(t.ali));
     t.transfer_from(to_key(t.ali), to_key(t.joe), amount, Sender(t.bob));
 }
+
+// ------------ START - Expiring Allowance Tests ------------
+
+#[test]
+fn should_transfer_erc20_from_before_expiry() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.block_time = 1_000;
+    t.approve_with_expiry(to_key(t.bob), 10.into(), 2_000, Sender(t.ali));
+    t.block_time = 2_000;
+    t.transfer_from(to_key(t.ali), to_key(t.joe), 3.into(), Sender(t.bob));
+    assert_eq!(t.balance_of(to_key(t.joe)), 3.into());
+    assert_eq!(t.allowance(to_key(t.ali), to_key(t.bob)), 7.into());
+}
+
+#[test]
+#[should_panic = "User(65533)"]
+fn should_not_transfer_erc20_from_after_expiry() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.block_time = 1_000;
+    t.approve_with_expiry(to_key(t.bob), 10.into(), 2_000, Sender(t.ali));
+    t.block_time = 2_001;
+    t.transfer_from(to_key(t.ali), to_key(t.joe), 3.into(), Sender(t.bob));
+}
+
+#[test]
+#[should_panic = "User(65533)"]
+fn should_keep_expiry_after_partial_transfer_from() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.block_time = 1_000;
+    t.approve_with_expiry(to_key(t.bob), 10.into(), 2_000, Sender(t.ali));
+    t.transfer_from(to_key(t.ali), to_key(t.joe), 3.into(), Sender(t.bob));
+    t.block_time = 3_000;
+    t.transfer_from(to_key(t.ali), to_key(t.joe), 3.into(), Sender(t.bob));
+}
+
+// ------------ START - Recurring Allowance Tests ------------
+
+#[test]
+fn should_collect_once_per_period() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.block_time = 1_000;
+    t.approve_recurring(to_key(t.bob), 10.into(), 1_000, Sender(t.ali));
+    t.block_time = 1_500;
+    t.collect(to_key(t.ali), to_key(t.bob), 6.into(), Sender(t.bob));
+    t.collect(to_key(t.ali), to_key(t.bob), 4.into(), Sender(t.bob));
+    t.block_time = 2_000;
+    t.collect(to_key(t.ali), to_key(t.bob), 10.into(), Sender(t.bob));
+    assert_eq!(t.balance_of(to_key(t.bob)), 20.into());
+}
+
+#[test]
+#[should_panic = "User(65533)"]
+fn should_not_collect_more_than_amount_per_period() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.block_time = 1_000;
+    t.approve_recurring(to_key(t.bob), 10.into(), 1_000, Sender(t.ali));
+    t.collect(to_key(t.ali), to_key(t.bob), 10.into(), Sender(t.bob));
+    t.block_time = 1_999;
+    t.collect(to_key(t.ali), to_key(t.bob), 1.into(), Sender(t.bob));
+}
+
+#[test]
+#[should_panic = "User(65512)"]
+fn should_not_approve_recurring_with_zero_period() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.approve_recurring(to_key(t.bob), 10.into(), 0, Sender(t.ali));
+}
+
+// ------------ START - Permit Tests ------------
+
+/// Returns a token with 10 tokens held by the account of `secret_key`.
+fn token_with_signer(secret_key: &SecretKey) -> Token {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    let owner = Key::Account(PublicKey::from(secret_key).to_account_hash());
+    t.transfer(owner, 10.into(), Sender(t.ali));
+    t
+}
+
+#[test]
+fn should_approve_with_ed25519_permit() {
+    let secret_key = SecretKey::ed25519_from_bytes([1u8; 32]).unwrap();
+    let public_key = PublicKey::from(&secret_key);
+    let owner = Key::Account(public_key.to_account_hash());
+    let mut t = token_with_signer(&secret_key);
+    let signature = t.sign_permit(&secret_key, to_key(t.bob), 5.into(), 1_000);
+    t.permit(
+        public_key,
+        to_key(t.bob),
+        5.into(),
+        1_000,
+        signature,
+        Sender(t.joe),
+    );
+    assert_eq!(t.allowance(owner, to_key(t.bob)), 5.into());
+    assert_eq!(t.nonces(owner), 1);
+    t.transfer_from(owner, to_key(t.joe), 5.into(), Sender(t.bob));
+    assert_eq!(t.balance_of(to_key(t.joe)), 5.into());
+}
+
+#[test]
+fn should_approve_with_secp256k1_permit() {
+    let secret_key = SecretKey::secp256k1_from_bytes([1u8; 32]).unwrap();
+    let public_key = PublicKey::from(&secret_key);
+    let owner = Key::Account(public_key.to_account_hash());
+    let mut t = token_with_signer(&secret_key);
+    let signature = t.sign_permit(&secret_key, to_key(t.bob), 5.into(), 1_000);
+    t.permit(
+        public_key,
+        to_key(t.bob),
+        5.into(),
+        1_000,
+        signature,
+        Sender(t.joe),
+    );
+    assert_eq!(t.allowance(owner, to_key(t.bob)), 5.into());
+}
+
+#[test]
+#[should_panic = "User(65510)"]
+fn should_not_replay_permit() {
+    let secret_key = SecretKey::ed25519_from_bytes([1u8; 32]).unwrap();
+    let public_key = PublicKey::from(&secret_key);
+    let mut t = token_with_signer(&secret_key);
+    let signature = t.sign_permit(&secret_key, to_key(t.bob), 5.into(), 1_000);
+    t.permit(
+        public_key.clone(),
+        to_key(t.bob),
+        5.into(),
+        1_000,
+        signature.clone(),
+        Sender(t.joe),
+    );
+    t.permit(
+        public_key,
+        to_key(t.bob),
+        5.into(),
+        1_000,
+        signature,
+        Sender(t.joe),
+    );
+}
+
+#[test]
+#[should_panic = "User(65510)"]
+fn should_not_permit_a_different_amount() {
+    let secret_key = SecretKey::ed25519_from_bytes([1u8; 32]).unwrap();
+    let public_key = PublicKey::from(&secret_key);
+    let mut t = token_with_signer(&secret_key);
+    let signature = t.sign_permit(&secret_key, to_key(t.bob), 5.into(), 1_000);
+    t.permit(
+        public_key,
+        to_key(t.bob),
+        10.into(),
+        1_000,
+        signature,
+        Sender(t.joe),
+    );
+}
+
+#[test]
+#[should_panic = "User(65511)"]
+fn should_not_permit_after_deadline() {
+    let secret_key = SecretKey::ed25519_from_bytes([1u8; 32]).unwrap();
+    let public_key = PublicKey::from(&secret_key);
+    let mut t = token_with_signer(&secret_key);
+    let signature = t.sign_permit(&secret_key, to_key(t.bob), 5.into(), 1_000);
+    t.block_time = 1_001;
+    t.permit(
+        public_key,
+        to_key(t.bob),
+        5.into(),
+        1_000,
+        signature,
+        Sender(t.joe),
+    );
+}
+
+// ------------ START - Blacklist Tests ------------
+
+#[test]
+fn should_blacklist_and_unblacklist() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.blacklist(to_key(t.bob), Sender(t.ali));
+    assert!(t.is_blacklisted(to_key(t.bob)));
+    t.unblacklist(to_key(t.bob), Sender(t.ali));
+    assert!(!t.is_blacklisted(to_key(t.bob)));
+    t.transfer(to_key(t.bob), 10.into(), Sender(t.ali));
+    assert_eq!(t.balance_of(to_key(t.bob)), 10.into());
+}
+
+#[test]
+#[should_panic = "User(65504)"]
+fn should_not_transfer_to_blacklisted_recipient() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.blacklist(to_key(t.bob), Sender(t.ali));
+    t.transfer(to_key(t.bob), 10.into(), Sender(t.ali));
+}
+
+#[test]
+#[should_panic = "User(65504)"]
+fn should_not_transfer_from_blacklisted_sender() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.transfer(to_key(t.bob), 10.into(), Sender(t.ali));
+    t.blacklist(to_key(t.bob), Sender(t.ali));
+    t.transfer(to_key(t.joe), 10.into(), Sender(t.bob));
+}
+
+#[test]
+#[should_panic = "User(65504)"]
+fn should_not_transfer_from_as_blacklisted_spender() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.approve(to_key(t.bob), 10.into(), Sender(t.ali));
+    t.blacklist(to_key(t.bob), Sender(t.ali));
+    t.transfer_from(to_key(t.ali), to_key(t.joe), 10.into(), Sender(t.bob));
+}
+
+#[test]
+#[should_panic = "User(65504)"]
+fn should_not_operator_send_as_blacklisted_operator() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.authorize_operator(to_key(t.bob), Sender(t.ali));
+    t.blacklist(to_key(t.bob), Sender(t.ali));
+    t.operator_send(to_key(t.ali), to_key(t.joe), 10.into(), Sender(t.bob));
+}
+
+#[test]
+#[should_panic = "User(65504)"]
+fn should_not_collect_as_blacklisted_spender() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.approve_recurring(to_key(t.bob), 10.into(), 1_000, Sender(t.ali));
+    t.blacklist(to_key(t.bob), Sender(t.ali));
+    t.collect(to_key(t.ali), to_key(t.joe), 10.into(), Sender(t.bob));
+}
+
+#[test]
+#[should_panic = "User(65504)"]
+fn should_not_authorize_blacklisted_operator() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.blacklist(to_key(t.bob), Sender(t.ali));
+    t.authorize_operator(to_key(t.bob), Sender(t.ali));
+}
+
+#[test]
+#[should_panic = "User(65526)"]
+fn should_not_blacklist_without_admin() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.blacklist(to_key(t.joe), Sender(t.bob));
+}
+
+// ------------ START - Allowlist Tests ------------
+
+/// Deploys a token in allowlist mode with `ali` allowlisted.
+fn token_with_allowlist() -> Token {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.set_allowlist_required(true, Sender(t.ali));
+    t.allowlist_add(vec![(to_key(t.ali), None)], Sender(t.ali));
+    t
+}
+
+#[test]
+fn should_transfer_between_allowlisted_holders() {
+    let mut t = token_with_allowlist();
+    t.allowlist_add(
+        vec![(to_key(t.bob), None), (to_key(t.joe), None)],
+        Sender(t.ali),
+    );
+    assert!(t.is_allowlisted(to_key(t.bob)));
+    t.transfer(to_key(t.bob), 10.into(), Sender(t.ali));
+    t.transfer(to_key(t.joe), 4.into(), Sender(t.bob));
+    assert_eq!(t.balance_of(to_key(t.joe)), 4.into());
+}
+
+#[test]
+#[should_panic = "User(65503)"]
+fn should_not_transfer_to_holder_off_the_allowlist() {
+    let mut t = token_with_allowlist();
+    t.transfer(to_key(t.bob), 10.into(), Sender(t.ali));
+}
+
+#[test]
+#[should_panic = "User(65503)"]
+fn should_not_transfer_to_removed_holder() {
+    let mut t = token_with_allowlist();
+    t.allowlist_add(vec![(to_key(t.bob), None)], Sender(t.ali));
+    t.allowlist_remove(vec![to_key(t.bob)], Sender(t.ali));
+    assert!(!t.is_allowlisted(to_key(t.bob)));
+    t.transfer(to_key(t.bob), 10.into(), Sender(t.ali));
+}
+
+#[test]
+#[should_panic = "User(65503)"]
+fn should_not_transfer_to_holder_with_expired_entry() {
+    let mut t = token_with_allowlist();
+    t.block_time = 1_000;
+    t.allowlist_add(vec![(to_key(t.bob), Some(2_000))], Sender(t.ali));
+    t.transfer(to_key(t.bob), 10.into(), Sender(t.ali));
+    t.block_time = 2_001;
+    t.transfer(to_key(t.bob), 10.into(), Sender(t.ali));
+}
+
+#[test]
+fn should_manage_allowlist_with_granted_kyc_role() {
+    let mut t = token_with_allowlist();
+    t.grant_kyc_role(to_key(t.bob), Sender(t.ali));
+    t.allowlist_add(vec![(to_key(t.joe), None)], Sender(t.bob));
+    assert!(t.is_allowlisted(to_key(t.joe)));
+}
+
+#[test]
+#[should_panic = "User(65526)"]
+fn should_not_manage_allowlist_without_kyc_role() {
+    let mut t = token_with_allowlist();
+    t.allowlist_add(vec![(to_key(t.bob), None)], Sender(t.bob));
+}
+
+// ------------ START - Transfer Restriction Tests ------------
+
+#[test]
+fn should_detect_restriction_without_reverting() {
+    let mut t = token_with_allowlist();
+    t.blacklist(to_key(t.bob), Sender(t.ali));
+    assert_eq!(
+        t.detect_transfer_restriction(to_key(t.ali), to_key(t.bob), 10.into()),
+        5
+    );
+    assert_eq!(
+        t.detect_transfer_restriction(to_key(t.ali), to_key(t.joe), 10.into()),
+        6
+    );
+    assert_eq!(
+        t.detect_transfer_restriction(to_key(t.joe), to_key(t.ali), 10.into()),
+        4
+    );
+    assert_eq!(
+        t.detect_transfer_restriction(to_key(t.ali), Key::Hash([0u8; 32]), 10.into()),
+        2
+    );
+
+    t.allowlist_add(vec![(to_key(t.joe), None)], Sender(t.ali));
+    assert_eq!(
+        t.detect_transfer_restriction(to_key(t.ali), to_key(t.joe), 10.into()),
+        0
+    );
+    assert_eq!(
+        t.detect_transfer_restriction(to_key(t.joe), to_key(t.ali), 10.into()),
+        7
+    );
+}
+
+#[test]
+fn should_detect_restriction_of_unregistered_receiver_first() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    let receiver = t.deploy_receiver(true);
+    t.set_safe_transfers(true, Sender(t.ali));
+    t.blacklist(to_key(t.ali), Sender(t.ali));
+    assert_eq!(
+        t.detect_transfer_restriction(to_key(t.ali), receiver, U256::MAX),
+        1
+    );
+}
+
+#[test]
+fn should_call_message_for_transfer_restriction() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    assert_eq!(t.message_for_transfer_restriction(0), "No restriction");
+    assert_eq!(
+        t.message_for_transfer_restriction(1),
+        "Recipient contract is not a registered receiver"
+    );
+    assert_eq!(
+        t.message_for_transfer_restriction(2),
+        "Sender or recipient is the null address"
+    );
+    assert_eq!(
+        t.message_for_transfer_restriction(3),
+        "Sender is blacklisted"
+    );
+    assert_eq!(
+        t.message_for_transfer_restriction(4),
+        "Sender is not allowlisted"
+    );
+    assert_eq!(
+        t.message_for_transfer_restriction(5),
+        "Recipient is blacklisted"
+    );
+    assert_eq!(
+        t.message_for_transfer_restriction(6),
+        "Recipient is not allowlisted"
+    );
+    assert_eq!(
+        t.message_for_transfer_restriction(7),
+        "Sender does not have enough balance"
+    );
+    assert_eq!(
+        t.message_for_transfer_restriction(u8::MAX),
+        "Unknown restriction code"
+    );
+}
+
+// ------------ START - Flash Loan Tests ------------
+
+#[test]
+fn should_flash_loan() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    let receiver = t.deploy_receiver(true);
+    t.flash_loan(receiver, 500.into(), Bytes::from(vec![1, 2]), Sender(t.bob));
+    assert_eq!(t.query_receiver::<Key>("last_from"), to_key(t.bob));
+    assert_eq!(t.query_receiver::<U256>("last_amount"), 500.into());
+    assert_eq!(t.balance_of(receiver), 0.into());
+    assert_eq!(t.total_supply(), token_cfg::total_supply());
+}
+
+#[test]
+fn should_pay_flash_fee_to_recipient() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    let receiver = t.deploy_receiver(true);
+    t.set_flash_fee(100, Sender(t.ali));
+    t.set_flash_fee_recipient(Some(to_key(t.joe)), Sender(t.ali));
+    t.transfer(receiver, 5.into(), Sender(t.ali));
+    t.flash_loan(receiver, 500.into(), Bytes::new(), Sender(t.bob));
+    assert_eq!(t.balance_of(to_key(t.joe)), 5.into());
+    assert_eq!(t.balance_of(receiver), 0.into());
+    assert_eq!(t.total_supply(), token_cfg::total_supply());
+}
+
+#[test]
+#[should_panic = "User(65534)"]
+fn should_not_flash_loan_without_paying_the_fee() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    let receiver = t.deploy_receiver(true);
+    t.set_flash_fee(100, Sender(t.ali));
+    t.flash_loan(receiver, 500.into(), Bytes::new(), Sender(t.bob));
+}
+
+#[test]
+#[should_panic = "User(65516)"]
+fn should_not_flash_loan_to_rejecting_receiver() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    let receiver = t.deploy_receiver(false);
+    t.flash_loan(receiver, 500.into(), Bytes::new(), Sender(t.bob));
+}
+
+#[test]
+#[should_panic = "User(65504)"]
+fn should_not_flash_loan_to_blacklisted_receiver() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    let receiver = t.deploy_receiver(true);
+    t.blacklist(receiver, Sender(t.ali));
+    t.flash_loan(receiver, 500.into(), Bytes::new(), Sender(t.bob));
+}
+
+#[test]
+#[should_panic = "User(65503)"]
+fn should_not_flash_loan_to_receiver_off_the_allowlist() {
+    let mut t = token_with_allowlist();
+    let receiver = t.deploy_receiver(true);
+    t.flash_loan(receiver, 500.into(), Bytes::new(), Sender(t.bob));
+}
+
+#[test]
+#[should_panic = "User(65526)"]
+fn should_not_set_flash_fee_without_admin() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.set_flash_fee(100, Sender(t.bob));
+}
+
+#[test]
+#[should_panic = "User(65502)"]
+fn should_not_set_flash_fee_above_denominator() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.set_flash_fee(10_001, Sender(t.ali));
+}
+
+// ------------ START - Governor Tests ------------
+
+/// Returns a token whose governor holds 100 tokens and has proposal 1, which sends 50 of them
+/// to `joe`. `ali` delegated her votes to herself and can vote on it from block time 3_000 to
+/// 13_000, with 900 votes.
+fn token_with_proposal(quorum: U256) -> (Token, Key) {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    let governor = t.deploy_governor(10.into(), quorum, (1_000, 10_000, 1_000));
+    t.block_time = 1_000;
+    t.delegate(to_key(t.ali), Sender(t.ali));
+    t.block_time = 1_500;
+    t.transfer(governor, 100.into(), Sender(t.ali));
+    t.block_time = 2_000;
+    let token = t.package_key();
+    t.propose(
+        token,
+        "transfer",
+        runtime_args! {
+            "recipient" => to_key(t.joe),
+            "amount" => U256::from(50),
+        },
+        Sender(t.ali),
+    );
+    (t, governor)
+}
+
+#[test]
+fn should_execute_passed_proposal() {
+    let (mut t, governor) = token_with_proposal(100.into());
+    t.block_time = 4_000;
+    t.cast_vote(1, 1, Sender(t.ali));
+    t.cast_vote(1, 0, Sender(t.bob));
+    assert_eq!(t.proposal_votes(1), (0.into(), 900.into(), 0.into()));
+    t.block_time = 14_000;
+    t.call_governor("queue", 1, Sender(t.bob));
+    assert_eq!(t.proposal_execution(1), (15_000, false, false));
+    t.block_time = 15_000;
+    t.call_governor("execute", 1, Sender(t.bob));
+    assert_eq!(t.proposal_execution(1), (15_000, true, false));
+    assert_eq!(t.balance_of(to_key(t.joe)), 50.into());
+    assert_eq!(t.balance_of(governor), 50.into());
+}
+
+#[test]
+#[should_panic = "User(65462)"]
+fn should_not_execute_before_timelock() {
+    let (mut t, _) = token_with_proposal(100.into());
+    t.block_time = 4_000;
+    t.cast_vote(1, 1, Sender(t.ali));
+    t.block_time = 14_000;
+    t.call_governor("queue", 1, Sender(t.bob));
+    t.block_time = 14_500;
+    t.call_governor("execute", 1, Sender(t.bob));
+}
+
+#[test]
+#[should_panic = "User(65464)"]
+fn should_not_queue_proposal_without_quorum() {
+    let (mut t, _) = token_with_proposal(1_000.into());
+    t.block_time = 4_000;
+    t.cast_vote(1, 1, Sender(t.ali));
+    t.block_time = 14_000;
+    t.call_governor("queue", 1, Sender(t.bob));
+}
+
+#[test]
+#[should_panic = "User(65466)"]
+fn should_not_vote_twice() {
+    let (mut t, _) = token_with_proposal(100.into());
+    t.block_time = 4_000;
+    t.cast_vote(1, 1, Sender(t.ali));
+    t.cast_vote(1, 1, Sender(t.ali));
+}
+
+#[test]
+#[should_panic = "User(65470)"]
+fn should_not_propose_below_threshold() {
+    let (mut t, _) = token_with_proposal(100.into());
+    let token = t.package_key();
+    t.propose(token, "snapshot", runtime_args! {}, Sender(t.bob));
+}
+
+// ------------ START - Snapshot Tests ------------
+
+#[test]
+fn should_read_balances_at_snapshot() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.transfer(to_key(t.bob), 10.into(), Sender(t.ali));
+    t.snapshot(Sender(t.ali));
+    t.transfer(to_key(t.bob), 5.into(), Sender(t.ali));
+    t.snapshot(Sender(t.ali));
+    t.transfer(to_key(t.joe), 3.into(), Sender(t.bob));
+    assert_eq!(t.balance_of_at(to_key(t.bob), 1), 10.into());
+    assert_eq!(t.balance_of_at(to_key(t.bob), 2), 15.into());
+    assert_eq!(t.balance_of(to_key(t.bob)), 12.into());
+    assert_eq!(t.balance_of_at(to_key(t.joe), 1), 0.into());
+    assert_eq!(t.total_supply_at(1), token_cfg::total_supply());
+}
+
+#[test]
+fn should_read_unchanged_balances_at_every_snapshot() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.transfer(to_key(t.bob), 10.into(), Sender(t.ali));
+    t.snapshot(Sender(t.ali));
+    t.snapshot(Sender(t.ali));
+    assert_eq!(t.balance_of_at(to_key(t.bob), 1), 10.into());
+    assert_eq!(t.balance_of_at(to_key(t.bob), 2), 10.into());
+    t.transfer(to_key(t.joe), 4.into(), Sender(t.bob));
+    assert_eq!(t.balance_of_at(to_key(t.bob), 1), 10.into());
+    assert_eq!(t.balance_of_at(to_key(t.bob), 2), 10.into());
+}
+
+#[test]
+fn should_read_balances_at_many_snapshots() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    for _ in 0..5 {
+        t.snapshot(Sender(t.ali));
+        t.transfer(to_key(t.bob), 1.into(), Sender(t.ali));
+    }
+    for id in 1..=5u64 {
+        assert_eq!(t.balance_of_at(to_key(t.bob), id), U256::from(id - 1));
+    }
+    assert_eq!(t.balance_of(to_key(t.bob)), 5.into());
+}
+
+#[test]
+#[should_panic = "User(65526)"]
+fn should_not_snapshot_without_admin() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.snapshot(Sender(t.bob));
+}
+
+// ------------ START - Votes Tests ------------
+
+#[test]
+fn should_count_votes_once_delegated() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    assert_eq!(t.get_votes(to_key(t.ali)), 0.into());
+    t.delegate(to_key(t.ali), Sender(t.ali));
+    assert_eq!(t.delegates(to_key(t.ali)), Some(to_key(t.ali)));
+    assert_eq!(t.get_votes(to_key(t.ali)), token_cfg::total_supply());
+    t.transfer(to_key(t.bob), 10.into(), Sender(t.ali));
+    assert_eq!(t.get_votes(to_key(t.ali)), 990.into());
+}
+
+#[test]
+fn should_move_votes_between_delegates() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.transfer(to_key(t.bob), 10.into(), Sender(t.ali));
+    t.delegate(to_key(t.joe), Sender(t.bob));
+    t.delegate(to_key(t.joe), Sender(t.ali));
+    assert_eq!(t.get_votes(to_key(t.joe)), token_cfg::total_supply());
+    t.delegate(to_key(t.ali), Sender(t.bob));
+    assert_eq!(t.get_votes(to_key(t.joe)), 990.into());
+    assert_eq!(t.get_votes(to_key(t.ali)), 10.into());
+    t.transfer(to_key(t.bob), 5.into(), Sender(t.ali));
+    assert_eq!(t.get_votes(to_key(t.joe)), 985.into());
+    assert_eq!(t.get_votes(to_key(t.ali)), 15.into());
+}
+
+#[test]
+fn should_not_write_when_delegating_to_the_current_delegate() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.delegate(to_key(t.ali), Sender(t.ali));
+    let first = t.last_gas_cost();
+    t.delegate(to_key(t.ali), Sender(t.ali));
+    assert!(t.last_gas_cost() < first);
+    assert_eq!(t.get_votes(to_key(t.ali)), token_cfg::total_supply());
+}
+
+#[test]
+fn should_read_past_votes() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.block_time = 1_000;
+    t.delegate(to_key(t.ali), Sender(t.ali));
+    t.block_time = 2_000;
+    t.transfer(to_key(t.bob), 100.into(), Sender(t.ali));
+    t.block_time = 3_000;
+    assert_eq!(t.get_past_votes(to_key(t.ali), 999), 0.into());
+    assert_eq!(t.get_past_votes(to_key(t.ali), 1_500), 1_000.into());
+    assert_eq!(t.get_past_votes(to_key(t.ali), 2_000), 900.into());
+}
+
+#[test]
+#[should_panic = "User(65506)"]
+fn should_not_read_votes_at_current_block_time() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.block_time = 1_000;
+    t.delegate(to_key(t.ali), Sender(t.ali));
+    t.get_past_votes(to_key(t.ali), 1_000);
+}
+
+#[test]
+fn should_delegate_by_signature() {
+    let secret_key = SecretKey::ed25519_from_bytes([1u8; 32]).unwrap();
+    let public_key = PublicKey::from(&secret_key);
+    let delegator = Key::Account(public_key.to_account_hash());
+    let mut t = token_with_signer(&secret_key);
+    let signature = t.sign_delegation(&secret_key, to_key(t.bob), 1_000);
+    t.delegate_by_sig(public_key, to_key(t.bob), 1_000, signature, Sender(t.joe));
+    assert_eq!(t.delegates(delegator), Some(to_key(t.bob)));
+    assert_eq!(t.get_votes(to_key(t.bob)), 10.into());
+    assert_eq!(t.nonces(delegator), 1);
+}
+
+#[test]
+#[should_panic = "User(65510)"]
+fn should_not_delegate_by_signature_of_another_account() {
+    let secret_key = SecretKey::ed25519_from_bytes([1u8; 32]).unwrap();
+    let other_key = SecretKey::ed25519_from_bytes([2u8; 32]).unwrap();
+    let mut t = token_with_signer(&secret_key);
+    let signature = t.sign_delegation(&other_key, to_key(t.bob), 1_000);
+    t.delegate_by_sig(
+        PublicKey::from(&secret_key),
+        to_key(t.bob),
+        1_000,
+        signature,
+        Sender(t.joe),
+    );
+}
+
+// ------------ START - Transfer With Authorization Tests ------------
+
+#[test]
+fn should_transfer_with_authorization() {
+    let secret_key = SecretKey::ed25519_from_bytes([1u8; 32]).unwrap();
+    let public_key = PublicKey::from(&secret_key);
+    let from = Key::Account(public_key.to_account_hash());
+    let mut t = token_with_signer(&secret_key);
+    t.block_time = 1_000;
+    let signature = t.sign_transfer_authorization(
+        &secret_key,
+        to_key(t.bob),
+        5.into(),
+        1.into(),
+        0,
+        2_000,
+        [7u8; 32],
+    );
+    t.transfer_with_authorization(
+        public_key,
+        to_key(t.bob),
+        5.into(),
+        1.into(),
+        (0, 2_000),
+        [7u8; 32],
+        signature,
+        Sender(t.joe),
+    );
+    assert_eq!(t.balance_of(to_key(t.bob)), 5.into());
+    assert_eq!(t.balance_of(to_key(t.joe)), 1.into());
+    assert_eq!(t.balance_of(from), 4.into());
+    assert!(t.authorization_state(from, [7u8; 32]));
+}
+
+#[test]
+fn should_accept_authorizations_in_any_order() {
+    let secret_key = SecretKey::secp256k1_from_bytes([1u8; 32]).unwrap();
+    let public_key = PublicKey::from(&secret_key);
+    let mut t = token_with_signer(&secret_key);
+    t.block_time = 1_000;
+    let first = t.sign_transfer_authorization(
+        &secret_key,
+        to_key(t.bob),
+        2.into(),
+        0.into(),
+        0,
+        2_000,
+        [1u8; 32],
+    );
+    let second = t.sign_transfer_authorization(
+        &secret_key,
+        to_key(t.bob),
+        3.into(),
+        0.into(),
+        0,
+        2_000,
+        [2u8; 32],
+    );
+    t.transfer_with_authorization(
+        public_key.clone(),
+        to_key(t.bob),
+        3.into(),
+        0.into(),
+        (0, 2_000),
+        [2u8; 32],
+        second,
+        Sender(t.joe),
+    );
+    t.transfer_with_authorization(
+        public_key,
+        to_key(t.bob),
+        2.into(),
+        0.into(),
+        (0, 2_000),
+        [1u8; 32],
+        first,
+        Sender(t.joe),
+    );
+    assert_eq!(t.balance_of(to_key(t.bob)), 5.into());
+}
+
+#[test]
+#[should_panic = "User(65509)"]
+fn should_not_transfer_with_authorization_before_valid_after() {
+    let secret_key = SecretKey::ed25519_from_bytes([1u8; 32]).unwrap();
+    let public_key = PublicKey::from(&secret_key);
+    let mut t = token_with_signer(&secret_key);
+    t.block_time = 1_000;
+    let signature = t.sign_transfer_authorization(
+        &secret_key,
+        to_key(t.bob),
+        5.into(),
+        0.into(),
+        1_000,
+        2_000,
+        [7u8; 32],
+    );
+    t.transfer_with_authorization(
+        public_key,
+        to_key(t.bob),
+        5.into(),
+        0.into(),
+        (1_000, 2_000),
+        [7u8; 32],
+        signature,
+        Sender(t.joe),
+    );
+}
+
+#[test]
+#[should_panic = "User(65510)"]
+fn should_not_raise_the_signed_relayer_fee() {
+    let secret_key = SecretKey::ed25519_from_bytes([1u8; 32]).unwrap();
+    let public_key = PublicKey::from(&secret_key);
+    let mut t = token_with_signer(&secret_key);
+    t.block_time = 1_000;
+    let signature = t.sign_transfer_authorization(
+        &secret_key,
+        to_key(t.bob),
+        5.into(),
+        1.into(),
+        0,
+        2_000,
+        [7u8; 32],
+    );
+    t.transfer_with_authorization(
+        public_key,
+        to_key(t.bob),
+        5.into(),
+        5.into(),
+        (0, 2_000),
+        [7u8; 32],
+        signature,
+        Sender(t.joe),
+    );
+}
+
+#[test]
+#[should_panic = "User(65508)"]
+fn should_not_transfer_with_canceled_authorization() {
+    let secret_key = SecretKey::ed25519_from_bytes([1u8; 32]).unwrap();
+    let public_key = PublicKey::from(&secret_key);
+    let mut t = token_with_signer(&secret_key);
+    t.block_time = 1_000;
+    let signature = t.sign_transfer_authorization(
+        &secret_key,
+        to_key(t.bob),
+        5.into(),
+        0.into(),
+        0,
+        2_000,
+        [7u8; 32],
+    );
+    let cancellation = t.sign_cancel_authorization(&secret_key, [7u8; 32]);
+    t.cancel_authorization(public_key.clone(), [7u8; 32], cancellation, Sender(t.joe));
+    t.transfer_with_authorization(
+        public_key,
+        to_key(t.bob),
+        5.into(),
+        0.into(),
+        (0, 2_000),
+        [7u8; 32],
+        signature,
+        Sender(t.joe),
+    );
+}
+
+// ------------ START - Revoke All Allowances Tests ------------
+
+#[test]
+fn should_index_spenders() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.approve(to_key(t.bob), 10.into(), Sender(t.ali));
+    t.approve(to_key(t.joe), 10.into(), Sender(t.ali));
+    t.approve(to_key(t.bob), 20.into(), Sender(t.ali));
+    assert_eq!(
+        t.spenders_of(to_key(t.ali)),
+        vec![to_key(t.bob), to_key(t.joe)]
+    );
+}
+
+#[test]
+fn should_revoke_all_allowances() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.approve(to_key(t.bob), 10.into(), Sender(t.ali));
+    t.approve_with_expiry(to_key(t.joe), 10.into(), 5_000, Sender(t.ali));
+    t.revoke_all_allowances(Sender(t.ali));
+    assert_eq!(t.allowance(to_key(t.ali), to_key(t.bob)), 0.into());
+    assert_eq!(t.allowance(to_key(t.ali), to_key(t.joe)), 0.into());
+    assert!(t.spenders_of(to_key(t.ali)).is_empty());
+}
+
+#[test]
+fn should_revoke_all_allowances_of_blacklisted_spender() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.approve(to_key(t.bob), 10.into(), Sender(t.ali));
+    t.approve(to_key(t.joe), 10.into(), Sender(t.ali));
+    t.blacklist(to_key(t.bob), Sender(t.ali));
+    t.revoke_all_allowances(Sender(t.ali));
+    assert_eq!(t.allowance(to_key(t.ali), to_key(t.bob)), 0.into());
+    assert_eq!(t.allowance(to_key(t.ali), to_key(t.joe)), 0.into());
+}
+
+#[test]
+fn should_revoke_all_allowances_as_blacklisted_owner() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.approve(to_key(t.bob), 10.into(), Sender(t.joe));
+    t.blacklist(to_key(t.joe), Sender(t.ali));
+    t.revoke_all_allowances(Sender(t.joe));
+    assert_eq!(t.allowance(to_key(t.joe), to_key(t.bob)), 0.into());
+}
+
+#[test]
+#[should_panic = "User(65533)"]
+fn should_not_collect_after_revoking_all_allowances() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.approve_recurring(to_key(t.bob), 10.into(), 1_000, Sender(t.ali));
+    t.revoke_all_allowances(Sender(t.ali));
+    t.collect(to_key(t.ali), to_key(t.bob), 1.into(), Sender(t.bob));
+}
+
+// ------------ START - Memo Tests ------------
+
+#[test]
+fn should_transfer_erc20_with_memo() {
+    let amount = 10.into();
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.transfer_with_memo(to_key(t.bob), amount, "deposit-4242", Sender(t.ali));
+    assert_eq!(t.balance_of(to_key(t.bob)), amount);
+}
+
+#[test]
+fn should_transfer_erc20_from_with_memo() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.approve(to_key(t.bob), 10.into(), Sender(t.ali));
+    t.transfer_from_with_memo(
+        to_key(t.ali),
+        to_key(t.joe),
+        3.into(),
+        "deposit-4242",
+        Sender(t.bob),
+    );
+    assert_eq!(t.balance_of(to_key(t.joe)), 3.into());
+    assert_eq!(t.allowance(to_key(t.ali), to_key(t.bob)), 7.into());
+}
+
+#[test]
+#[should_panic = "User(65518)"]
+fn should_not_transfer_erc20_with_too_long_memo() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    let memo = "x".repeat(257);
+    t.transfer_with_memo(to_key(t.bob), 10.into(), &memo, Sender(t.ali));
+}
+
+// ------------ START - Transfer And Call Tests ------------
+
+#[test]
+fn should_transfer_and_call_receiver() {
+    let amount = 10.into();
+    let data = Bytes::from(vec![1u8, 2, 3]);
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    let receiver = t.deploy_receiver(true);
+    t.transfer_and_call(receiver, amount, data.clone(), Sender(t.ali));
+    assert_eq!(t.balance_of(receiver), amount);
+    assert_eq!(t.query_receiver::<Key>("last_from"), to_key(t.ali));
+    assert_eq!(t.query_receiver::<U256>("last_amount"), amount);
+    assert_eq!(t.query_receiver::<Bytes>("last_data"), data);
+}
+
+#[test]
+#[should_panic = "User(65516)"]
+fn should_not_transfer_and_call_rejecting_receiver() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    let receiver = t.deploy_receiver(false);
+    t.transfer_and_call(receiver, 10.into(), Bytes::new(), Sender(t.ali));
+}
+
+#[test]
+#[should_panic = "User(65517)"]
+fn should_not_transfer_and_call_account() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.transfer_and_call(to_key(t.bob), 10.into(), Bytes::new(), Sender(t.ali));
+}
+
+#[test]
+fn should_approve_and_call_receiver() {
+    let amount = 10.into();
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    let receiver = t.deploy_receiver(true);
+    t.approve_and_call(receiver, amount, Bytes::new(), Sender(t.ali));
+    assert_eq!(t.allowance(to_key(t.ali), receiver), amount);
+    assert_eq!(t.query_receiver::<U256>("last_amount"), amount);
+}
+
+#[test]
+#[should_panic = "User(65516)"]
+fn should_not_approve_and_call_rejecting_receiver() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    let receiver = t.deploy_receiver(false);
+    t.approve_and_call(receiver, 10.into(), Bytes::new(), Sender(t.ali));
+}
+
+#[test]
+#[should_panic = "User(65514)"]
+fn should_not_reenter_transfer_and_call() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    let receiver = t.deploy_malicious_receiver();
+    t.transfer_and_call(receiver, 10.into(), Bytes::new(), Sender(t.ali));
+}
+
+#[test]
+#[should_panic = "User(65514)"]
+fn should_not_reenter_approve_and_call() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    let receiver = t.deploy_malicious_receiver();
+    t.approve_and_call(receiver, 10.into(), Bytes::new(), Sender(t.ali));
+}
+
+#[test]
+fn should_release_reentrancy_lock_after_transfer_and_call() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    let receiver = t.deploy_receiver(true);
+    t.transfer_and_call(receiver, 10.into(), Bytes::new(), Sender(t.ali));
+    t.transfer_and_call(receiver, 10.into(), Bytes::new(), Sender(t.ali));
+    assert_eq!(t.balance_of(receiver), 20.into());
+}
+
+// ------------ START - Operator Tests ------------
+
+#[test]
+fn should_authorize_and_revoke_operator() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    assert!(!t.is_operator_for(to_key(t.bob), to_key(t.ali)));
+    t.authorize_operator(to_key(t.bob), Sender(t.ali));
+    assert!(t.is_operator_for(to_key(t.bob), to_key(t.ali)));
+    t.revoke_operator(to_key(t.bob), Sender(t.ali));
+    assert!(!t.is_operator_for(to_key(t.bob), to_key(t.ali)));
+}
+
+#[test]
+fn should_operator_send() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.authorize_operator(to_key(t.bob), Sender(t.ali));
+    t.operator_send(to_key(t.ali), to_key(t.joe), 10.into(), Sender(t.bob));
+    assert_eq!(t.balance_of(to_key(t.joe)), 10.into());
+    assert_eq!(
+        t.balance_of(to_key(t.ali)),
+        token_cfg::total_supply() - U256::from(10)
+    );
+}
+
+#[test]
+#[should_panic = "User(65513)"]
+fn should_not_operator_send_without_authorization() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.operator_send(to_key(t.ali), to_key(t.joe), 10.into(), Sender(t.bob));
+}
+
+#[test]
+#[should_panic = "User(65513)"]
+fn should_not_operator_send_after_revocation() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.authorize_operator(to_key(t.bob), Sender(t.ali));
+    t.revoke_operator(to_key(t.bob), Sender(t.ali));
+    t.operator_send(to_key(t.ali), to_key(t.joe), 10.into(), Sender(t.bob));
+}
+
+// ------------ START - Safe Transfer Tests ------------
+
+#[test]
+fn should_transfer_to_unregistered_contract_by_default() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    let receiver = t.deploy_receiver(true);
+    t.transfer(receiver, 10.into(), Sender(t.ali));
+    assert_eq!(t.balance_of(receiver), 10.into());
+}
+
+#[test]
+#[should_panic = "User(65515)"]
+fn should_not_transfer_to_unregistered_contract_with_safe_transfers() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    let receiver = t.deploy_receiver(true);
+    t.set_safe_transfers(true, Sender(t.ali));
+    t.transfer(receiver, 10.into(), Sender(t.ali));
+}
+
+#[test]
+fn should_transfer_to_registered_contract_with_safe_transfers() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    let receiver = t.deploy_receiver(true);
+    t.set_safe_transfers(true, Sender(t.ali));
+    t.register_receiver(Sender(t.bob));
+    t.transfer(receiver, 10.into(), Sender(t.ali));
+    assert_eq!(t.balance_of(receiver), 10.into());
+}
+
+#[test]
+fn should_trust_registration_without_calling_the_hook() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    let receiver = t.deploy_receiver(false);
+    t.set_safe_transfers(true, Sender(t.ali));
+    t.register_receiver(Sender(t.bob));
+    // The receiver rejects every callback, but a plain transfer never asks it.
+    t.transfer(receiver, 10.into(), Sender(t.ali));
+    assert_eq!(t.balance_of(receiver), 10.into());
+    assert_eq!(t.query_receiver::<U256>("last_amount"), 0.into());
+}
+
+#[test]
+fn should_transfer_and_call_unregistered_contract_with_safe_transfers() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    let receiver = t.deploy_receiver(true);
+    t.set_safe_transfers(true, Sender(t.ali));
+    t.transfer_and_call(receiver, 10.into(), Bytes::new(), Sender(t.ali));
+    assert_eq!(t.balance_of(receiver), 10.into());
+}
+
+#[test]
+fn should_transfer_to_account_with_safe_transfers() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.set_safe_transfers(true, Sender(t.ali));
+    t.transfer(to_key(t.bob), 10.into(), Sender(t.ali));
+    assert_eq!(t.balance_of(to_key(t.bob)), 10.into());
+}
+
+#[test]
+#[should_panic = "User(65526)"]
+fn should_not_set_safe_transfers_without_admin() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.set_safe_transfers(true, Sender(t.bob));
+}
+
+// ------------ START - Batch Transfer Tests ------------
+
+#[test]
+fn should_batch_transfer_erc20() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.batch_transfer(
+        vec![(to_key(t.bob), 10.into()), (to_key(t.joe), 20.into())],
+        Sender(t.ali),
+    );
+    assert_eq!(
+        t.balance_of(to_key(t.ali)),
+        token_cfg::total_supply() - U256::from(30)
+    );
+    assert_eq!(t.balance_of(to_key(t.bob)), 10.into());
+    assert_eq!(t.balance_of(to_key(t.joe)), 20.into());
+}
+
+#[test]
+#[should_panic = "User(65534)"]
+fn should_not_batch_transfer_more_than_balance() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.batch_transfer(
+        vec![
+            (to_key(t.bob), token_cfg::total_supply()),
+            (to_key(t.joe), 1.into()),
+        ],
+        Sender(t.ali),
+    );
+}
+
+#[test]
+#[should_panic = "User(65531)"]
+fn should_not_batch_transfer_to_zero_address() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.batch_transfer(
+        vec![
+            (to_key(t.bob), 1.into()),
+            (Key::Account(AccountHash::default()), 1.into()),
+        ],
+        Sender(t.ali),
+    );
+}
+
+#[test]
+fn should_batch_transfer_erc20_from() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.transfer(to_key(t.bob), 100.into(), Sender(t.ali));
+    t.approve(to_key(t.joe), 10.into(), Sender(t.ali));
+    t.approve(to_key(t.joe), 20.into(), Sender(t.bob));
+    t.batch_transfer_from(
+        vec![
+            (to_key(t.ali), to_key(t.joe), 4.into()),
+            (to_key(t.bob), to_key(t.ali), 20.into()),
+        ],
+        Sender(t.joe),
+    );
+    assert_eq!(t.balance_of(to_key(t.joe)), 4.into());
+    assert_eq!(t.balance_of(to_key(t.bob)), 80.into());
+    assert_eq!(t.allowance(to_key(t.ali), to_key(t.joe)), 6.into());
+    assert_eq!(t.allowance(to_key(t.bob), to_key(t.joe)), 0.into());
+}
+
+#[test]
+#[should_panic = "User(65533)"]
+fn should_not_batch_transfer_erc20_from_without_every_allowance() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.transfer(to_key(t.bob), 100.into(), Sender(t.ali));
+    t.approve(to_key(t.joe), 10.into(), Sender(t.ali));
+    t.batch_transfer_from(
+        vec![
+            (to_key(t.ali), to_key(t.joe), 4.into()),
+            (to_key(t.bob), to_key(t.joe), 1.into()),
+        ],
+        Sender(t.joe),
+    );
+}
+
+// ------------ START - Batch Read Tests ------------
+
+#[test]
+fn should_call_balances_of() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.transfer(to_key(t.bob), 10.into(), Sender(t.ali));
+    let balances = t.balances_of(
+        vec![to_key(t.ali), to_key(t.bob), to_key(t.joe)],
+        Sender(t.bob),
+    );
+    assert_eq!(
+        balances,
+        vec![
+            token_cfg::total_supply() - U256::from(10),
+            10.into(),
+            0.into()
+        ]
+    );
+}
+
+#[test]
+fn should_call_allowances_of() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.approve(to_key(t.bob), 10.into(), Sender(t.ali));
+    let allowances = t.allowances_of(
+        vec![
+            (to_key(t.ali), to_key(t.bob)),
+            (to_key(t.bob), to_key(t.ali)),
+        ],
+        Sender(t.bob),
+    );
+    assert_eq!(allowances, vec![U256::from(10), U256::zero()]);
+}
+
+// ------------ START - Metadata Tests ------------
+
+#[test]
+fn should_set_metadata() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    assert_eq!(t.metadata("website"), None);
+    t.set_metadata("website", "https://friendly.market", Sender(t.ali));
+    assert_eq!(
+        t.metadata("website"),
+        Some("https://friendly.market".to_string())
+    );
+}
+
+#[test]
+#[should_panic = "User(65525)"]
+fn should_not_set_unknown_metadata_key() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.set_metadata("telegram", "@friendlymarket", Sender(t.ali));
+}
+
+#[test]
+#[should_panic = "User(65526)"]
+fn should_not_set_metadata_without_admin() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.set_metadata("website", "https://friendly.market", Sender(t.bob));
+}
+
+#[test]
+fn should_set_name_and_symbol() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.set_name("Friendly Token", Sender(t.ali));
+    t.set_symbol("FRND", Sender(t.ali));
+    assert_eq!(t.name(), "Friendly Token");
+    assert_eq!(t.symbol(), "FRND");
+}
+
+#[test]
+#[should_panic = "User(65526)"]
+fn should_not_set_name_without_admin() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.set_name("Friendly Token", Sender(t.bob));
+}
+
+#[test]
+fn should_call_token_info() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    t.set_metadata("website", "https://friendly.market", Sender(t.ali));
+    let (details, total_supply, metadata) = t.token_info(Sender(t.bob));
+    assert_eq!(
+        details,
+        ("ERC20".to_string(), "ERC".to_string(), token_cfg::DECIMALS)
+    );
+    assert_eq!(total_supply, token_cfg::total_supply());
+    assert_eq!(metadata.len(), 1);
+    assert_eq!(
+        metadata.get("website").map(String::as_str),
+        Some("https://friendly.market")
+    );
+}
+
+// ------------ START - Install Validation Tests ------------
+
+#[test]
+#[should_panic = "User(65524)"]
+fn should_not_install_with_empty_name() {
+    Token::deployed_from(ERC20_WASM, "", "ERC");
+}
+
+#[test]
+#[should_panic = "User(65523)"]
+fn should_not_install_with_slash_in_name() {
+    Token::deployed_from(ERC20_WASM, "ERC/20", "ERC");
+}
+
+#[test]
+#[should_panic = "User(65522)"]
+fn should_not_install_over_existing_named_key() {
+    let mut t = Token::deployed_from(ERC20_WASM, "ERC20", "ERC");
+    // `ERC20_package_hash` was already put under ali's account by the first install.
+    t.deploy_another(ERC20_WASM, "ERC20_package", "ERC");
+}
+
+#[test]
+#[should_panic = "User(65521)"]
+fn should_not_install_with_empty_symbol() {
+    Token::deployed_from(ERC20_WASM, "ERC20", "");
+}
+
+#[test]
+#[should_panic = "User(65520)"]
+fn should_not_install_with_invalid_symbol_character() {
+    Token::deployed_from(ERC20_WASM, "ERC20", "ER C");
+}
+
+#[test]
+#[should_panic = "User(65519)"]
+fn should_not_install_with_too_many_decimals() {
+    Token::deployed_with_decimals(ERC20_WASM, "ERC20", "ERC", 255);
+}
+
+#[test]
+fn should_install_with_max_decimals() {
+    let t = Token::deployed_with_decimals(ERC20_WASM, "ERC20", "ERC", 18);
+    assert_eq!(t.decimals(), 18);
+}
+
+// ------------ START - Gas Tests ------------
+
+/// Runs `op` against a fresh deployment of `wasm` and returns the gas it spent.
+fn gas_cost_of(wasm: &str, op: fn(&mut Token)) -> U512 {
+    let mut t = Token::deployed_from(wasm, "ERC20", "ERC");
+    op(&mut t);
+    t.last_gas_cost()
+}
+
+/// Returns the gas `op` spends on the baseline build and on the current build, in that order.
+/// The baseline cost is the limit the current build is held to.
+fn gas_before_and_after(op: fn(&mut Token)) -> (U512, U512) {
+    (
+        gas_cost_of(ERC20_BASELINE_WASM, op),
+        gas_cost_of(ERC20_WASM, op),
+    )
+}
+
+#[test]
+fn should_transfer_with_less_gas_than_baseline() {
+    let (baseline, cost) =
+        gas_before_and_after(|t| t.transfer(to_key(t.bob), 10.into(), Sender(t.ali)));
+    assert!(
+        cost < baseline,
+        "transfer costs {} gas, baseline {}",
+        cost,
+        baseline
+    );
+}
+
+#[test]
+fn should_not_approve_with_more_gas_than_baseline() {
+    let (baseline, cost) =
+        gas_before_and_after(|t| t.approve(to_key(t.bob), 10.into(), Sender(t.ali)));
+    assert!(
+        cost <= baseline,
+        "approve costs {} gas, baseline {}",
+        cost,
+        baseline
+    );
+}
+
+#[test]
+fn should_transfer_from_with_less_gas_than_baseline() {
+    let (baseline, cost) = gas_before_and_after(|t| {
+        t.approve(to_key(t.bob), 10.into(), Sender(t.ali));
+        t.transfer_from(to_key(t.ali), to_key(t.joe), 3.into(), Sender(t.bob))
+    });
+    assert!(
+        cost < baseline,
+        "transfer_from costs {} gas, baseline {}",
+        cost,
+        baseline
+    );
+}
+
+#[test]
+fn should_install_with_less_gas_than_baseline() {
+    let (baseline, cost) = gas_before_and_after(|_| {});
+    assert!(
+        cost < baseline,
+        "install costs {} gas, baseline {}",
+        cost,
+        baseline
+    );
+}
+
+#[test]
+fn should_keep_wasm_under_size_budget() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("wasm")
+        .join(ERC20_WASM);
+    let size = fs::metadata(&path).expect("should have built wasm").len();
+    assert!(
+        size <= ERC20_WASM_SIZE_BUDGET,
+        "{} is {} bytes, budget {}",
+        ERC20_WASM,
+        size,
+        ERC20_WASM_SIZE_BUDGET
+    );
+}